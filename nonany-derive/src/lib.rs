@@ -0,0 +1,221 @@
+//! Derive macros for [nonany](https://docs.rs/nonany).
+//!
+//! This crate is not meant to be used directly. Enable the `derive` feature of nonany instead. It requires Rust 1.71,
+//! which is the MSRV of `syn` 2, while nonany itself supports Rust 1.56.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Expr, ExprLit, ExprUnary, Fields, Ident, Lit, LitStr, Path, UnOp};
+
+/// Derives `NonAnyRepr` for a fieldless enum with an integer `#[repr]`.
+///
+/// See the documentation of the `NonAnyRepr` trait in nonany for details.
+#[proc_macro_derive(NonAnyRepr, attributes(nonany))]
+pub fn derive_nonany_repr(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into()
+    }
+}
+
+struct Repr {
+    int: Ident,
+    nonany: Ident,
+    min: i128,
+    max: i128,
+    pointer_sized: bool,
+}
+
+impl Repr {
+    fn parse(input: &DeriveInput) -> Result<Self, Error> {
+        let mut repr = None;
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+            attr.parse_nested_meta(|meta| {
+                if let Some(ident) = meta.path.get_ident() {
+                    if let Some(found) = Self::from_ident(ident) {
+                        repr = Some(found);
+                    }
+                }
+                Ok(())
+            })?;
+        }
+
+        repr.ok_or_else(|| Error::new(Span::call_site(), "NonAnyRepr requires an integer representation such as `#[repr(u8)]`"))
+    }
+
+    fn from_ident(ident: &Ident) -> Option<Self> {
+        let (nonany, min, max, pointer_sized) = match ident.to_string().as_str() {
+            "i8" => ("NonAnyI8", i8::MIN as i128, i8::MAX as i128, false),
+            "i16" => ("NonAnyI16", i16::MIN as i128, i16::MAX as i128, false),
+            "i32" => ("NonAnyI32", i32::MIN as i128, i32::MAX as i128, false),
+            "i64" => ("NonAnyI64", i64::MIN as i128, i64::MAX as i128, false),
+            "i128" => ("NonAnyI128", i128::MIN, i128::MAX, false),
+            "isize" => ("NonAnyIsize", 0, 0, true),
+            "u8" => ("NonAnyU8", 0, u8::MAX as i128, false),
+            "u16" => ("NonAnyU16", 0, u16::MAX as i128, false),
+            "u32" => ("NonAnyU32", 0, u32::MAX as i128, false),
+            "u64" => ("NonAnyU64", 0, u64::MAX as i128, false),
+            "u128" => ("NonAnyU128", 0, u128::MAX as i128, false),
+            "usize" => ("NonAnyUsize", 0, 0, true),
+            _ => return None
+        };
+
+        Some(Self {
+            int: ident.clone(),
+            nonany: Ident::new(nonany, ident.span()),
+            min,
+            max,
+            pointer_sized,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    niche: Option<Expr>,
+    krate: Option<Path>,
+}
+
+impl Options {
+    fn parse(input: &DeriveInput) -> Result<Self, Error> {
+        let mut options = Self::default();
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("nonany")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("niche") {
+                    options.niche = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    options.krate = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown nonany attribute, expected `niche` or `crate`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(Error::new(Span::call_site(), "NonAnyRepr can only be derived for enums"))
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(input.generics.span(), "NonAnyRepr cannot be derived for generic enums"));
+    }
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(variant.span(), "NonAnyRepr can only be derived for fieldless enums"));
+        }
+    }
+
+    let repr = Repr::parse(input)?;
+    let options = Options::parse(input)?;
+
+    let niche = match options.niche {
+        Some(niche) => quote!(#niche),
+        None => pick_niche(input, &repr)?
+    };
+
+    let krate = match options.krate {
+        Some(krate) => quote!(#krate),
+        None => quote!(::nonany)
+    };
+
+    let name = &input.ident;
+    let int = &repr.int;
+    let nonany = &repr.nonany;
+    let nonany = quote!(#krate::#nonany<{ #niche }>);
+    let variants = data.variants.iter().map(|variant| &variant.ident).collect::<Vec<_>>();
+
+    let checks = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        quote_spanned! {variant.span()=>
+            let _: [(); 0] = [(); ((#name::#ident as #int) == (#niche)) as usize];
+        }
+    });
+
+    Ok(quote! {
+        const _: () = {
+            #(#checks)*
+        };
+
+        impl #krate::NonAnyRepr for #name {
+            type NonAny = #nonany;
+        }
+
+        impl ::core::convert::From<#name> for #nonany {
+            fn from(value: #name) -> Self {
+                // SAFETY: None of the discriminants are equal to the niche, which is checked at compile time.
+                unsafe { Self::new_unchecked(value as #int) }
+            }
+        }
+
+        impl ::core::convert::TryFrom<#nonany> for #name {
+            type Error = #nonany;
+            fn try_from(value: #nonany) -> ::core::result::Result<Self, Self::Error> {
+                match value.get() {
+                    #(discriminant if discriminant == #name::#variants as #int => ::core::result::Result::Ok(#name::#variants),)*
+                    _ => ::core::result::Result::Err(value)
+                }
+            }
+        }
+    })
+}
+
+fn pick_niche(input: &DeriveInput, repr: &Repr) -> Result<TokenStream2, Error> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => unreachable!()
+    };
+
+    let mut discriminants = Vec::new();
+    let mut next = Some(0i128);
+    for variant in &data.variants {
+        let discriminant = match &variant.discriminant {
+            Some((_, expr)) => eval_discriminant(expr),
+            None => next
+        };
+
+        match discriminant {
+            Some(discriminant) => discriminants.push(discriminant),
+            None => return Err(Error::new(variant.span(), "unable to determine the discriminant of this variant, specify the niche with `#[nonany(niche = ...)]`"))
+        }
+
+        next = discriminant.and_then(|discriminant| discriminant.checked_add(1));
+    }
+
+    let int = &repr.int;
+    let is_free = |value: i128| !discriminants.contains(&value);
+
+    if is_free(0) {
+        return Ok(quote!(0));
+    }
+    if !repr.pointer_sized && is_free(repr.max) {
+        return Ok(quote!(#int::MAX));
+    }
+    if !repr.pointer_sized && repr.min != 0 && is_free(repr.min) {
+        return Ok(quote!(#int::MIN));
+    }
+
+    // The discriminants are unique, so there is always a free value within `1..=discriminants.len()`.
+    let value = (1..).find(|value| is_free(*value)).unwrap();
+    let value = Literal::i128_unsuffixed(value);
+    Ok(quote!(#value))
+}
+
+fn eval_discriminant(expr: &Expr) -> Option<i128> {
+    match expr {
+        // Values are compared by their bit pattern, so `u128` discriminants above `i128::MAX` wrap around.
+        Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) => lit.base10_parse::<i128>().ok().or_else(|| lit.base10_parse::<u128>().ok().map(|value| value as i128)),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr, .. }) => eval_discriminant(expr).and_then(i128::checked_neg),
+        Expr::Group(group) => eval_discriminant(&group.expr),
+        Expr::Paren(paren) => eval_discriminant(&paren.expr),
+        _ => None
+    }
+}
@@ -0,0 +1,387 @@
+//! A generational arena with niche-optimized handles.
+//!
+//! An [`Arena`] stores values in a vector of slots, and hands out a [`Handle`] for every inserted value. A handle is a
+//! [`NonMaxU32`] slot index and a generation, so an `Option<Handle>` is 8 bytes. The generation of a slot is increased
+//! whenever its value is removed, so a handle to a removed value does not find the value that reuses its slot.
+//!
+//! ```rust
+//! use nonany::arena::{Arena, Handle};
+//!
+//! let mut arena = Arena::new();
+//! let a = arena.insert("a");
+//! let b = arena.insert("b");
+//!
+//! assert_eq!(arena[a], "a");
+//! assert_eq!(arena.remove(a), Some("a"));
+//! assert_eq!(arena.get(a), None);
+//!
+//! // The slot of `a` is reused, but the old handle stays invalid.
+//! let c = arena.insert("c");
+//! assert_eq!(c.index(), a.index());
+//! assert_eq!(arena.get(a), None);
+//! assert_eq!(arena.iter().map(|(_, value)| *value).collect::<Vec<_>>(), ["c", "b"]);
+//!
+//! assert_eq!(core::mem::size_of::<Option<Handle>>(), 8);
+//! # let _ = b;
+//! ```
+//!
+//! Vacant slots form a free list, which is linked through the slots themselves with `Option<NonMaxU32>` links, so an
+//! insertion reuses the most recently vacated slot. A slot whose generation would overflow is retired instead of being
+//! put back on the free list, so a handle can never match a value that it was not created for.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::{Enumerate, FromIterator};
+use core::ops::{Index, IndexMut};
+use crate::NonMaxU32;
+
+/// A handle to a value in an [`Arena`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    index: NonMaxU32,
+    generation: u32,
+}
+
+impl Handle {
+    /// Creates a handle from a slot index and a generation, as returned by [`into_raw_parts`](Self::into_raw_parts).
+    pub const fn from_raw_parts(index: NonMaxU32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+
+    /// Returns the slot index and the generation of the handle.
+    pub const fn into_raw_parts(self) -> (NonMaxU32, u32) {
+        (self.index, self.generation)
+    }
+
+    /// Returns the slot index of the handle.
+    pub const fn index(self) -> usize {
+        self.index.get() as usize
+    }
+
+    /// Returns the generation of the handle.
+    pub const fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+    Occupied(T),
+    Vacant(Option<NonMaxU32>),
+}
+
+#[derive(Clone)]
+struct Slot<T> {
+    generation: u32,
+    entry: Entry<T>,
+}
+
+/// A generational arena, which hands out a [`Handle`] for every inserted value.
+#[derive(Clone)]
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Option<NonMaxU32>,
+    len: usize,
+    retired: usize,
+}
+
+impl<T> Arena<T> {
+    /// Creates an empty arena.
+    pub const fn new() -> Self {
+        Self { slots: Vec::new(), free: None, len: 0, retired: 0 }
+    }
+
+    /// Creates an empty arena with space for at least `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { slots: Vec::with_capacity(capacity), free: None, len: 0, retired: 0 }
+    }
+
+    /// Returns the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the arena contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots the arena can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Reserves space for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        // Retired slots are never reused, so they do not count as space for new values.
+        let vacant = self.slots.len() - self.len - self.retired;
+        if additional > vacant {
+            self.slots.reserve(additional - vacant);
+        }
+    }
+
+    /// Inserts a value, and returns its handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena already has `u32::MAX` slots and none of them are vacant.
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.insert_with(|_| value)
+    }
+
+    /// Inserts the value returned by the function, which is given the handle of the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena already has `u32::MAX` slots and none of them are vacant.
+    pub fn insert_with(&mut self, f: impl FnOnce(Handle) -> T) -> Handle {
+        if let Some(index) = self.free {
+            let slot = &mut self.slots[index.get() as usize];
+            let handle = Handle { index, generation: slot.generation };
+            match slot.entry {
+                Entry::Vacant(next) => self.free = next,
+                Entry::Occupied(_) => unreachable!("the free list contains an occupied slot"),
+            }
+            slot.entry = Entry::Occupied(f(handle));
+            self.len += 1;
+            return handle;
+        }
+
+        let index = u32::try_from(self.slots.len()).ok().and_then(NonMaxU32::new).expect("arena is full");
+        let handle = Handle { index, generation: 0 };
+        self.slots.push(Slot { generation: 0, entry: Entry::Occupied(f(handle)) });
+        self.len += 1;
+        handle
+    }
+
+    /// Removes the value of the handle and returns it, or returns `None` if the handle is not valid.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let index = handle.index;
+        let slot = self.slots.get_mut(index.get() as usize)?;
+        if slot.generation != handle.generation || matches!(slot.entry, Entry::Vacant(_)) {
+            return None;
+        }
+
+        let value = match slot.generation.checked_add(1) {
+            Some(generation) => {
+                slot.generation = generation;
+                core::mem::replace(&mut slot.entry, Entry::Vacant(self.free.replace(index)))
+            }
+            None => {
+                self.retired += 1;
+                core::mem::replace(&mut slot.entry, Entry::Vacant(None))
+            }
+        };
+        self.len -= 1;
+
+        match value {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant(_) => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if the handle refers to a value in the arena.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Returns a reference to the value of the handle, or `None` if the handle is not valid.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        match self.slots.get(handle.index())? {
+            Slot { generation, entry: Entry::Occupied(value) } if *generation == handle.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value of the handle, or `None` if the handle is not valid.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        match self.slots.get_mut(handle.index())? {
+            Slot { generation, entry: Entry::Occupied(value) } if *generation == handle.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Removes all values for which the function returns `false`.
+    pub fn retain(&mut self, mut f: impl FnMut(Handle, &mut T) -> bool) {
+        for index in 0..self.slots.len() {
+            let slot = &mut self.slots[index];
+            let handle = Handle { index: slot_index(index), generation: slot.generation };
+            if let Entry::Occupied(value) = &mut slot.entry {
+                if !f(handle, value) {
+                    self.remove(handle);
+                }
+            }
+        }
+    }
+
+    /// Removes all values. Handles to the removed values stay invalid.
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false)
+    }
+
+    /// Returns an iterator over the handles and the values, in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { slots: self.slots.iter().enumerate(), len: self.len }
+    }
+
+    /// Returns an iterator over the handles and mutable references to the values, in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { slots: self.slots.iter_mut().enumerate(), len: self.len }
+    }
+
+    /// Returns an iterator over the handles, in slot order.
+    pub fn handles(&self) -> impl Iterator<Item = Handle> + '_ {
+        self.iter().map(|(handle, _)| handle)
+    }
+
+    /// Returns an iterator over the values, in slot order.
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over mutable references to the values, in slot order.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.iter_mut().map(|(_, value)| value)
+    }
+}
+
+fn slot_index(index: usize) -> NonMaxU32 {
+    // SAFETY: Slots are only pushed while their index is a valid `NonMaxU32`.
+    unsafe { NonMaxU32::new_unchecked(index as u32) }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Handle> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).expect("invalid arena handle")
+    }
+}
+
+impl<T> IndexMut<Handle> for Arena<T> {
+    fn index_mut(&mut self, handle: Handle) -> &mut T {
+        self.get_mut(handle).expect("invalid arena handle")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for Arena<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arena = Self::new();
+        arena.extend(iter);
+        arena
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+macro_rules! arena_iter {
+    ($(#[$attr:meta])* $iter:ident, $slots:ty, $item:ty, [$($lifetime:lifetime)?], $value:pat => $result:expr) => {
+        $(#[$attr])*
+        pub struct $iter<$($lifetime,)? T> {
+            slots: Enumerate<$slots>,
+            len: usize,
+        }
+
+        impl<$($lifetime,)? T> Iterator for $iter<$($lifetime,)? T> {
+            type Item = (Handle, $item);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                for (index, slot) in &mut self.slots {
+                    let generation = slot.generation;
+                    if let $value = slot.entry {
+                        self.len -= 1;
+                        return Some((Handle { index: slot_index(index), generation }, $result));
+                    }
+                }
+                None
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($lifetime,)? T> DoubleEndedIterator for $iter<$($lifetime,)? T> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                while let Some((index, slot)) = self.slots.next_back() {
+                    let generation = slot.generation;
+                    if let $value = slot.entry {
+                        self.len -= 1;
+                        return Some((Handle { index: slot_index(index), generation }, $result));
+                    }
+                }
+                None
+            }
+        }
+
+        impl<$($lifetime,)? T> ExactSizeIterator for $iter<$($lifetime,)? T> {}
+
+        impl<$($lifetime,)? T> core::iter::FusedIterator for $iter<$($lifetime,)? T> {}
+    };
+}
+
+arena_iter!(
+    /// An iterator over the handles and the values of an [`Arena`].
+    Iter, core::slice::Iter<'a, Slot<T>>, &'a T, ['a], Entry::Occupied(ref value) => value
+);
+
+arena_iter!(
+    /// An iterator over the handles and mutable references to the values of an [`Arena`].
+    IterMut, core::slice::IterMut<'a, Slot<T>>, &'a mut T, ['a], Entry::Occupied(ref mut value) => value
+);
+
+arena_iter!(
+    /// An iterator that moves the handles and the values out of an [`Arena`].
+    IntoIter, alloc::vec::IntoIter<Slot<T>>, T, [], Entry::Occupied(value) => value
+);
+
+impl<T> IntoIterator for Arena<T> {
+    type Item = (Handle, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { slots: self.slots.into_iter().enumerate(), len: self.len }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Arena<T> {
+    type Item = (Handle, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Arena<T> {
+    type Item = (Handle, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
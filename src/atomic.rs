@@ -0,0 +1,510 @@
+//! Atomic versions of the `NonAny*` types.
+//!
+//! An `AtomicNonAny*<NICHE>` stores the encoded value in a single atomic integer, and an `AtomicOptionNonAny*<NICHE>`
+//! does the same for an `Option<NonAny*<NICHE>>`, using the encoded zero as `None`. Both are the same size as the
+//! integer type.
+//!
+//! ```rust
+//! use core::sync::atomic::Ordering;
+//! use nonany::{NonMaxU32, atomic::{AtomicNonMaxU32, AtomicOptionNonMaxU32}};
+//!
+//! let counter = AtomicNonMaxU32::new(NonMaxU32::new(u32::MAX - 2).unwrap());
+//! assert_eq!(counter.fetch_add(1, Ordering::Relaxed).get(), u32::MAX - 2);
+//! assert_eq!(counter.fetch_add(1, Ordering::Relaxed).get(), u32::MAX - 1);
+//! assert_eq!(counter.load(Ordering::Relaxed).get(), 0);
+//!
+//! let slot = AtomicOptionNonMaxU32::default();
+//! assert_eq!(slot.swap(NonMaxU32::new(5), Ordering::AcqRel), None);
+//! assert_eq!(slot.take(Ordering::AcqRel), NonMaxU32::new(5));
+//! ```
+//!
+//! A `OnceNonAny*<NICHE>` is built on top of these, and can be initialized once. It uses the encoded zero to mark the
+//! cell as uninitialized, so it does not need a separate flag.
+//!
+//! ```rust
+//! use nonany::{NonMaxU64, atomic::OnceNonMaxU64};
+//!
+//! static CAPABILITIES: OnceNonMaxU64 = OnceNonMaxU64::new();
+//!
+//! let capabilities = CAPABILITIES.get_or_init(|| NonMaxU64::new(0b1011).unwrap());
+//! assert_eq!(capabilities.get(), 0b1011);
+//! assert_eq!(CAPABILITIES.set(NonMaxU64::new(0).unwrap()), Err(NonMaxU64::new(0).unwrap()));
+//! assert_eq!(core::mem::size_of::<OnceNonMaxU64>(), core::mem::size_of::<u64>());
+//! ```
+//!
+//! The arithmetic operations are implemented with a compare and swap loop, because the encoding does not preserve
+//! addition. The types are backed by `core::sync::atomic`, and are only available for the integer sizes that the
+//! target supports. With the `portable-atomic` feature they are backed by
+//! [portable-atomic](https://docs.rs/portable-atomic) instead, which provides all sizes on every target.
+
+use core::sync::atomic::Ordering;
+use crate::CheckedError;
+
+#[cfg(not(feature = "portable-atomic"))]
+pub(crate) use core::sync::atomic as imp;
+#[cfg(feature = "portable-atomic")]
+pub(crate) use portable_atomic as imp;
+
+/// Returns the strongest ordering that can be used for the failed load of a compare and swap with the given ordering.
+pub(crate) const fn load_ordering(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        order => order,
+    }
+}
+
+macro_rules! nonany_atomic {
+    ($atomic:ident, $option:ident, $nonany:ident, $nonzero:ident, $inner:ident, $int:ident, [$($atomic_alias:ident),+], [$($option_alias:ident),+]) => {
+        nonany_typedef!($($atomic_alias,)+ $atomic, $int);
+        nonany_typedef!($($option_alias,)+ $option, $int);
+
+        #[doc = concat!("A [`", stringify!($nonany), "`](crate::", stringify!($nonany), ") which can be safely shared between threads.")]
+        #[repr(transparent)]
+        pub struct $atomic<const NICHE: $int>(imp::$inner);
+
+        impl<const NICHE: $int> $atomic<NICHE> {
+            /// Creates a new atomic non-any.
+            pub const fn new(value: crate::$nonany<NICHE>) -> Self {
+                Self(imp::$inner::new(value.0.get()))
+            }
+
+            /// Consumes the atomic and returns the contained value.
+            pub fn into_inner(self) -> crate::$nonany<NICHE> {
+                Self::decode(self.0.into_inner())
+            }
+
+            /// Returns a mutable reference to the contained value.
+            pub fn get_mut(&mut self) -> &mut crate::$nonany<NICHE> {
+                // SAFETY: The non-any is `repr(transparent)` over the integer, and only non-zero bits are ever stored.
+                unsafe { &mut *(self.0.get_mut() as *mut $int as *mut crate::$nonany<NICHE>) }
+            }
+
+            /// Loads the value.
+            pub fn load(&self, order: Ordering) -> crate::$nonany<NICHE> {
+                Self::decode(self.0.load(order))
+            }
+
+            /// Stores a value.
+            pub fn store(&self, value: crate::$nonany<NICHE>, order: Ordering) {
+                self.0.store(value.0.get(), order)
+            }
+
+            /// Stores a value, returning the previous value.
+            pub fn swap(&self, value: crate::$nonany<NICHE>, order: Ordering) -> crate::$nonany<NICHE> {
+                Self::decode(self.0.swap(value.0.get(), order))
+            }
+
+            /// Stores a value if the current value is the same as `current`.
+            ///
+            /// Returns the previous value, which is `Ok` if it was equal to `current`.
+            pub fn compare_exchange(&self, current: crate::$nonany<NICHE>, new: crate::$nonany<NICHE>, success: Ordering, failure: Ordering) -> Result<crate::$nonany<NICHE>, crate::$nonany<NICHE>> {
+                self.0.compare_exchange(current.0.get(), new.0.get(), success, failure)
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Stores a value if the current value is the same as `current`, but is allowed to spuriously fail.
+            ///
+            /// Returns the previous value, which is `Ok` if it was equal to `current` and the value was stored.
+            pub fn compare_exchange_weak(&self, current: crate::$nonany<NICHE>, new: crate::$nonany<NICHE>, success: Ordering, failure: Ordering) -> Result<crate::$nonany<NICHE>, crate::$nonany<NICHE>> {
+                self.0.compare_exchange_weak(current.0.get(), new.0.get(), success, failure)
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Fetches the value, and applies a function to it that returns an optional new value.
+            ///
+            /// Returns `Ok` with the previous value if the function returned `Some`, and `Err` otherwise.
+            pub fn fetch_update<F>(&self, set_order: Ordering, fetch_order: Ordering, mut f: F) -> Result<crate::$nonany<NICHE>, crate::$nonany<NICHE>>
+            where
+                F: FnMut(crate::$nonany<NICHE>) -> Option<crate::$nonany<NICHE>>,
+            {
+                self.0.fetch_update(set_order, fetch_order, |bits| f(Self::decode(bits)).map(|value| value.0.get()))
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Adds to the value with wrapping, skipping the niche, and returns the previous value.
+            ///
+            /// If the result would be the niche, the next value in the direction of the addition is stored instead.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn fetch_add(&self, value: $int, order: Ordering) -> crate::$nonany<NICHE> {
+                match self.fetch_update(order, load_ordering(order), |current| Some(nonany_atomic!(@skip, $nonany, current.get().wrapping_add(value), value < 0))) {
+                    Ok(previous) | Err(previous) => previous
+                }
+            }
+
+            /// Subtracts from the value with wrapping, skipping the niche, and returns the previous value.
+            ///
+            /// If the result would be the niche, the next value in the direction of the subtraction is stored instead.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn fetch_sub(&self, value: $int, order: Ordering) -> crate::$nonany<NICHE> {
+                match self.fetch_update(order, load_ordering(order), |current| Some(nonany_atomic!(@skip, $nonany, current.get().wrapping_sub(value), value >= 0))) {
+                    Ok(previous) | Err(previous) => previous
+                }
+            }
+
+            /// Adds to the value and returns the previous value, or leaves the value as is and returns an error if
+            /// the result overflows or is the niche.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn checked_fetch_add(&self, value: $int, order: Ordering) -> Result<crate::$nonany<NICHE>, CheckedError> {
+                let mut error = CheckedError::Overflow;
+                self.fetch_update(order, load_ordering(order), |current| nonany_atomic!(@checked, $nonany, error, current.get().checked_add(value), value < 0))
+                    .map_err(|_| error)
+            }
+
+            /// Subtracts from the value and returns the previous value, or leaves the value as is and returns an
+            /// error if the result overflows or is the niche.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn checked_fetch_sub(&self, value: $int, order: Ordering) -> Result<crate::$nonany<NICHE>, CheckedError> {
+                let mut error = CheckedError::Overflow;
+                self.fetch_update(order, load_ordering(order), |current| nonany_atomic!(@checked, $nonany, error, current.get().checked_sub(value), value >= 0))
+                    .map_err(|_| error)
+            }
+
+            fn decode(bits: $int) -> crate::$nonany<NICHE> {
+                // SAFETY: Only the non-zero bits of a non-any are ever stored.
+                crate::$nonany(unsafe { core::num::$nonzero::new_unchecked(bits) })
+            }
+        }
+
+        impl<const NICHE: $int> From<crate::$nonany<NICHE>> for $atomic<NICHE> {
+            fn from(value: crate::$nonany<NICHE>) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl<const NICHE: $int> core::fmt::Debug for $atomic<NICHE> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+
+        #[doc = concat!("An `Option<", stringify!($nonany), ">` which can be safely shared between threads.")]
+        ///
+        /// The encoded zero is used as `None`, so this is the same size as the integer type.
+        #[repr(transparent)]
+        pub struct $option<const NICHE: $int>(imp::$inner);
+
+        impl<const NICHE: $int> $option<NICHE> {
+            /// Creates a new atomic optional non-any.
+            pub const fn new(value: Option<crate::$nonany<NICHE>>) -> Self {
+                Self(imp::$inner::new(Self::encode(value)))
+            }
+
+            /// Creates a new atomic optional non-any that contains `None`.
+            pub const fn none() -> Self {
+                Self(imp::$inner::new(0))
+            }
+
+            /// Consumes the atomic and returns the contained value.
+            pub fn into_inner(self) -> Option<crate::$nonany<NICHE>> {
+                Self::decode(self.0.into_inner())
+            }
+
+            /// Returns a mutable reference to the contained value.
+            pub fn get_mut(&mut self) -> &mut Option<crate::$nonany<NICHE>> {
+                // SAFETY: An option of the non-any has the same layout as the integer, with zero as `None`.
+                unsafe { &mut *(self.0.get_mut() as *mut $int as *mut Option<crate::$nonany<NICHE>>) }
+            }
+
+            /// Loads the value.
+            pub fn load(&self, order: Ordering) -> Option<crate::$nonany<NICHE>> {
+                Self::decode(self.0.load(order))
+            }
+
+            /// Stores a value.
+            pub fn store(&self, value: Option<crate::$nonany<NICHE>>, order: Ordering) {
+                self.0.store(Self::encode(value), order)
+            }
+
+            /// Stores a value, returning the previous value.
+            pub fn swap(&self, value: Option<crate::$nonany<NICHE>>, order: Ordering) -> Option<crate::$nonany<NICHE>> {
+                Self::decode(self.0.swap(Self::encode(value), order))
+            }
+
+            /// Stores `None`, returning the previous value.
+            pub fn take(&self, order: Ordering) -> Option<crate::$nonany<NICHE>> {
+                self.swap(None, order)
+            }
+
+            /// Stores a value if the current value is the same as `current`.
+            ///
+            /// Returns the previous value, which is `Ok` if it was equal to `current`.
+            pub fn compare_exchange(&self, current: Option<crate::$nonany<NICHE>>, new: Option<crate::$nonany<NICHE>>, success: Ordering, failure: Ordering) -> Result<Option<crate::$nonany<NICHE>>, Option<crate::$nonany<NICHE>>> {
+                self.0.compare_exchange(Self::encode(current), Self::encode(new), success, failure)
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Stores a value if the current value is the same as `current`, but is allowed to spuriously fail.
+            ///
+            /// Returns the previous value, which is `Ok` if it was equal to `current` and the value was stored.
+            pub fn compare_exchange_weak(&self, current: Option<crate::$nonany<NICHE>>, new: Option<crate::$nonany<NICHE>>, success: Ordering, failure: Ordering) -> Result<Option<crate::$nonany<NICHE>>, Option<crate::$nonany<NICHE>>> {
+                self.0.compare_exchange_weak(Self::encode(current), Self::encode(new), success, failure)
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Fetches the value, and applies a function to it that returns an optional new value.
+            ///
+            /// Returns `Ok` with the previous value if the function returned `Some`, and `Err` otherwise.
+            pub fn fetch_update<F>(&self, set_order: Ordering, fetch_order: Ordering, mut f: F) -> Result<Option<crate::$nonany<NICHE>>, Option<crate::$nonany<NICHE>>>
+            where
+                F: FnMut(Option<crate::$nonany<NICHE>>) -> Option<Option<crate::$nonany<NICHE>>>,
+            {
+                self.0.fetch_update(set_order, fetch_order, |bits| f(Self::decode(bits)).map(Self::encode))
+                    .map(Self::decode)
+                    .map_err(Self::decode)
+            }
+
+            /// Adds to the value with wrapping, skipping the niche, and returns the previous value.
+            ///
+            /// If the result would be the niche, the next value in the direction of the addition is stored instead. `None` is left as is.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn fetch_add(&self, value: $int, order: Ordering) -> Option<crate::$nonany<NICHE>> {
+                match self.fetch_update(order, load_ordering(order), |current| current.map(|current| Some(nonany_atomic!(@skip, $nonany, current.get().wrapping_add(value), value < 0)))) {
+                    Ok(previous) | Err(previous) => previous
+                }
+            }
+
+            /// Subtracts from the value with wrapping, skipping the niche, and returns the previous value.
+            ///
+            /// If the result would be the niche, the next value in the direction of the subtraction is stored instead. `None` is left as is.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn fetch_sub(&self, value: $int, order: Ordering) -> Option<crate::$nonany<NICHE>> {
+                match self.fetch_update(order, load_ordering(order), |current| current.map(|current| Some(nonany_atomic!(@skip, $nonany, current.get().wrapping_sub(value), value >= 0)))) {
+                    Ok(previous) | Err(previous) => previous
+                }
+            }
+
+            /// Adds to the value and returns the previous value, or leaves the value as is and returns an error if
+            /// the result overflows or is the niche. `None` is left as is.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn checked_fetch_add(&self, value: $int, order: Ordering) -> Result<Option<crate::$nonany<NICHE>>, CheckedError> {
+                let mut error = CheckedError::Overflow;
+                match self.fetch_update(order, load_ordering(order), |current| match current {
+                    Some(current) => nonany_atomic!(@checked, $nonany, error, current.get().checked_add(value), value < 0).map(Some),
+                    None => None,
+                }) {
+                    Ok(previous) => Ok(previous),
+                    Err(None) => Ok(None),
+                    Err(Some(_)) => Err(error),
+                }
+            }
+
+            /// Subtracts from the value and returns the previous value, or leaves the value as is and returns an
+            /// error if the result overflows or is the niche. `None` is left as is.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn checked_fetch_sub(&self, value: $int, order: Ordering) -> Result<Option<crate::$nonany<NICHE>>, CheckedError> {
+                let mut error = CheckedError::Overflow;
+                match self.fetch_update(order, load_ordering(order), |current| match current {
+                    Some(current) => nonany_atomic!(@checked, $nonany, error, current.get().checked_sub(value), value >= 0).map(Some),
+                    None => None,
+                }) {
+                    Ok(previous) => Ok(previous),
+                    Err(None) => Ok(None),
+                    Err(Some(_)) => Err(error),
+                }
+            }
+
+            const fn encode(value: Option<crate::$nonany<NICHE>>) -> $int {
+                match value {
+                    Some(value) => value.0.get(),
+                    None => 0,
+                }
+            }
+
+            fn decode(bits: $int) -> Option<crate::$nonany<NICHE>> {
+                core::num::$nonzero::new(bits).map(crate::$nonany)
+            }
+        }
+
+        impl<const NICHE: $int> Default for $option<NICHE> {
+            fn default() -> Self {
+                Self::none()
+            }
+        }
+
+        impl<const NICHE: $int> From<Option<crate::$nonany<NICHE>>> for $option<NICHE> {
+            fn from(value: Option<crate::$nonany<NICHE>>) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl<const NICHE: $int> From<crate::$nonany<NICHE>> for $option<NICHE> {
+            fn from(value: crate::$nonany<NICHE>) -> Self {
+                Self::new(Some(value))
+            }
+        }
+
+        impl<const NICHE: $int> core::fmt::Debug for $option<NICHE> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+    };
+
+    (@skip, $nonany:ident, $value:expr, $backward:expr) => {
+        match crate::$nonany::<NICHE>::new($value) {
+            Some(value) => value,
+            // SAFETY: The values next to the niche are never the niche.
+            None if $backward => unsafe { crate::$nonany::<NICHE>::new_unchecked(NICHE.wrapping_sub(1)) },
+            None => unsafe { crate::$nonany::<NICHE>::new_unchecked(NICHE.wrapping_add(1)) },
+        }
+    };
+
+    (@checked, $nonany:ident, $error:ident, $result:expr, $underflow:expr) => {
+        match $result {
+            Some(value) => match crate::$nonany::<NICHE>::new(value) {
+                Some(value) => Some(value),
+                None => {
+                    $error = CheckedError::Niche;
+                    None
+                }
+            },
+            None => {
+                $error = if $underflow { CheckedError::Underflow } else { CheckedError::Overflow };
+                None
+            }
+        }
+    };
+}
+
+
+macro_rules! nonany_once {
+    ($once:ident, $option:ident, $nonany:ident, $int:ident, [$($once_alias:ident),+]) => {
+        nonany_typedef!($($once_alias,)+ $once, $int);
+
+        #[doc = concat!("A [`", stringify!($nonany), "`](crate::", stringify!($nonany), ") which can be initialized once, and is safely shared between threads.")]
+        ///
+        /// The encoded zero is used to mark the cell as uninitialized, so this is the same size as the integer type.
+        /// When multiple threads initialize the cell at the same time, each of them may call the initialization
+        /// function, but only the first value is stored and returned by all of them.
+        #[derive(Default)]
+        #[repr(transparent)]
+        pub struct $once<const NICHE: $int>($option<NICHE>);
+
+        impl<const NICHE: $int> $once<NICHE> {
+            /// Creates a new uninitialized cell.
+            pub const fn new() -> Self {
+                Self($option::none())
+            }
+
+            /// Returns the value, or `None` if the cell is not initialized yet.
+            pub fn get(&self) -> Option<crate::$nonany<NICHE>> {
+                self.0.load(Ordering::Acquire)
+            }
+
+            /// Initializes the cell with the given value.
+            ///
+            /// Returns the value back as an error if the cell was already initialized.
+            pub fn set(&self, value: crate::$nonany<NICHE>) -> Result<(), crate::$nonany<NICHE>> {
+                match self.0.compare_exchange(None, Some(value), Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(value),
+                }
+            }
+
+            /// Returns the value, initializing the cell with the given function if it is not initialized yet.
+            pub fn get_or_init<F>(&self, f: F) -> crate::$nonany<NICHE>
+            where
+                F: FnOnce() -> crate::$nonany<NICHE>,
+            {
+                match self.get_or_try_init(|| Ok::<_, core::convert::Infallible>(f())) {
+                    Ok(value) => value,
+                    Err(never) => match never {},
+                }
+            }
+
+            /// Returns the value, initializing the cell with the given function if it is not initialized yet.
+            ///
+            /// If the function returns an error, the cell is left uninitialized and the error is returned.
+            pub fn get_or_try_init<F, E>(&self, f: F) -> Result<crate::$nonany<NICHE>, E>
+            where
+                F: FnOnce() -> Result<crate::$nonany<NICHE>, E>,
+            {
+                if let Some(value) = self.get() {
+                    return Ok(value);
+                }
+
+                let value = f()?;
+                match self.0.compare_exchange(None, Some(value), Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => Ok(value),
+                    // The exchange only fails if another thread initialized the cell first.
+                    Err(current) => Ok(current.unwrap_or(value)),
+                }
+            }
+
+            /// Returns a mutable reference to the value, or `None` if the cell is not initialized yet.
+            pub fn get_mut(&mut self) -> Option<&mut crate::$nonany<NICHE>> {
+                self.0.get_mut().as_mut()
+            }
+
+            /// Consumes the cell and returns the value, or `None` if the cell is not initialized yet.
+            pub fn into_inner(self) -> Option<crate::$nonany<NICHE>> {
+                self.0.into_inner()
+            }
+        }
+
+        impl<const NICHE: $int> From<crate::$nonany<NICHE>> for $once<NICHE> {
+            fn from(value: crate::$nonany<NICHE>) -> Self {
+                Self($option::new(Some(value)))
+            }
+        }
+
+        impl<const NICHE: $int> core::fmt::Debug for $once<NICHE> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self.get() {
+                    Some(value) => f.debug_tuple(stringify!($once)).field(&value).finish(),
+                    None => f.write_str(concat!(stringify!($once), "(<uninit>)")),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+nonany_atomic!(AtomicNonAnyI8, AtomicOptionNonAnyI8, NonAnyI8, NonZeroI8, AtomicI8, i8, [AtomicNonZeroI8, AtomicNonMinI8, AtomicNonMaxI8], [AtomicOptionNonZeroI8, AtomicOptionNonMinI8, AtomicOptionNonMaxI8]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "16"))]
+nonany_atomic!(AtomicNonAnyI16, AtomicOptionNonAnyI16, NonAnyI16, NonZeroI16, AtomicI16, i16, [AtomicNonZeroI16, AtomicNonMinI16, AtomicNonMaxI16], [AtomicOptionNonZeroI16, AtomicOptionNonMinI16, AtomicOptionNonMaxI16]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "32"))]
+nonany_atomic!(AtomicNonAnyI32, AtomicOptionNonAnyI32, NonAnyI32, NonZeroI32, AtomicI32, i32, [AtomicNonZeroI32, AtomicNonMinI32, AtomicNonMaxI32], [AtomicOptionNonZeroI32, AtomicOptionNonMinI32, AtomicOptionNonMaxI32]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "64"))]
+nonany_atomic!(AtomicNonAnyI64, AtomicOptionNonAnyI64, NonAnyI64, NonZeroI64, AtomicI64, i64, [AtomicNonZeroI64, AtomicNonMinI64, AtomicNonMaxI64], [AtomicOptionNonZeroI64, AtomicOptionNonMinI64, AtomicOptionNonMaxI64]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "ptr"))]
+nonany_atomic!(AtomicNonAnyIsize, AtomicOptionNonAnyIsize, NonAnyIsize, NonZeroIsize, AtomicIsize, isize, [AtomicNonZeroIsize, AtomicNonMinIsize, AtomicNonMaxIsize], [AtomicOptionNonZeroIsize, AtomicOptionNonMinIsize, AtomicOptionNonMaxIsize]);
+
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+nonany_atomic!(AtomicNonAnyU8, AtomicOptionNonAnyU8, NonAnyU8, NonZeroU8, AtomicU8, u8, [AtomicNonZeroU8, AtomicNonMinU8, AtomicNonMaxU8], [AtomicOptionNonZeroU8, AtomicOptionNonMinU8, AtomicOptionNonMaxU8]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "16"))]
+nonany_atomic!(AtomicNonAnyU16, AtomicOptionNonAnyU16, NonAnyU16, NonZeroU16, AtomicU16, u16, [AtomicNonZeroU16, AtomicNonMinU16, AtomicNonMaxU16], [AtomicOptionNonZeroU16, AtomicOptionNonMinU16, AtomicOptionNonMaxU16]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "32"))]
+nonany_atomic!(AtomicNonAnyU32, AtomicOptionNonAnyU32, NonAnyU32, NonZeroU32, AtomicU32, u32, [AtomicNonZeroU32, AtomicNonMinU32, AtomicNonMaxU32], [AtomicOptionNonZeroU32, AtomicOptionNonMinU32, AtomicOptionNonMaxU32]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "64"))]
+nonany_atomic!(AtomicNonAnyU64, AtomicOptionNonAnyU64, NonAnyU64, NonZeroU64, AtomicU64, u64, [AtomicNonZeroU64, AtomicNonMinU64, AtomicNonMaxU64], [AtomicOptionNonZeroU64, AtomicOptionNonMinU64, AtomicOptionNonMaxU64]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "ptr"))]
+nonany_atomic!(AtomicNonAnyUsize, AtomicOptionNonAnyUsize, NonAnyUsize, NonZeroUsize, AtomicUsize, usize, [AtomicNonZeroUsize, AtomicNonMinUsize, AtomicNonMaxUsize], [AtomicOptionNonZeroUsize, AtomicOptionNonMinUsize, AtomicOptionNonMaxUsize]);
+
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+nonany_once!(OnceNonAnyI8, AtomicOptionNonAnyI8, NonAnyI8, i8, [OnceNonZeroI8, OnceNonMinI8, OnceNonMaxI8]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "16"))]
+nonany_once!(OnceNonAnyI16, AtomicOptionNonAnyI16, NonAnyI16, i16, [OnceNonZeroI16, OnceNonMinI16, OnceNonMaxI16]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "32"))]
+nonany_once!(OnceNonAnyI32, AtomicOptionNonAnyI32, NonAnyI32, i32, [OnceNonZeroI32, OnceNonMinI32, OnceNonMaxI32]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "64"))]
+nonany_once!(OnceNonAnyI64, AtomicOptionNonAnyI64, NonAnyI64, i64, [OnceNonZeroI64, OnceNonMinI64, OnceNonMaxI64]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "ptr"))]
+nonany_once!(OnceNonAnyIsize, AtomicOptionNonAnyIsize, NonAnyIsize, isize, [OnceNonZeroIsize, OnceNonMinIsize, OnceNonMaxIsize]);
+
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+nonany_once!(OnceNonAnyU8, AtomicOptionNonAnyU8, NonAnyU8, u8, [OnceNonZeroU8, OnceNonMinU8, OnceNonMaxU8]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "16"))]
+nonany_once!(OnceNonAnyU16, AtomicOptionNonAnyU16, NonAnyU16, u16, [OnceNonZeroU16, OnceNonMinU16, OnceNonMaxU16]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "32"))]
+nonany_once!(OnceNonAnyU32, AtomicOptionNonAnyU32, NonAnyU32, u32, [OnceNonZeroU32, OnceNonMinU32, OnceNonMaxU32]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "64"))]
+nonany_once!(OnceNonAnyU64, AtomicOptionNonAnyU64, NonAnyU64, u64, [OnceNonZeroU64, OnceNonMinU64, OnceNonMaxU64]);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "ptr"))]
+nonany_once!(OnceNonAnyUsize, AtomicOptionNonAnyUsize, NonAnyUsize, usize, [OnceNonZeroUsize, OnceNonMinUsize, OnceNonMaxUsize]);
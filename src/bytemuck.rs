@@ -0,0 +1,57 @@
+//! [bytemuck](https://docs.rs/bytemuck) support for the `NonAny*` types.
+//!
+//! Note that casting works on the encoded representation: a `NonAny*` stores `value ^ NICHE` in memory, so the raw
+//! bytes of a `NonMaxU32` are the bitwise inverse of its value. The all-zero bit pattern is `None` for every
+//! `Option<NonAny*>`, which makes those options `Zeroable` and `Pod`.
+//!
+//! ```rust
+//! use nonany::NonMaxU32;
+//!
+//! let raw: [u32; 3] = [0, !7, !0 ^ 1];
+//! let values: &[Option<NonMaxU32>] = bytemuck::cast_slice(&raw);
+//! assert_eq!(values, [None, NonMaxU32::new(7), NonMaxU32::new(1)]);
+//!
+//! assert_eq!(bytemuck::checked::try_cast::<u32, NonMaxU32>(!7).unwrap().get(), 7);
+//! assert!(bytemuck::checked::try_cast::<u32, NonMaxU32>(0).is_err());
+//! ```
+
+use ::bytemuck::{CheckedBitPattern, NoUninit, PodInOption, TransparentWrapper, ZeroableInOption};
+
+macro_rules! nonany_bytemuck {
+    ($nonany:ident, $nonzero:ident, $int:ident) => {
+        // SAFETY: `NonAny*` is `repr(transparent)` over `NonZero*`, which has no padding or uninitialized bytes.
+        unsafe impl<const NICHE: $int> NoUninit for crate::$nonany<NICHE> {}
+
+        // SAFETY: Any nonzero encoded value is valid, and `NonAny*` has the same layout as the integer.
+        unsafe impl<const NICHE: $int> CheckedBitPattern for crate::$nonany<NICHE> {
+            type Bits = $int;
+
+            fn is_valid_bit_pattern(bits: &$int) -> bool {
+                *bits != 0
+            }
+        }
+
+        // SAFETY: `Option<NonAny*>` uses the zero encoded value as `None`, just like `Option<NonZero*>`.
+        unsafe impl<const NICHE: $int> ZeroableInOption for crate::$nonany<NICHE> {}
+
+        // SAFETY: Every bit pattern of `Option<NonAny*>` is valid, and there is no padding.
+        unsafe impl<const NICHE: $int> PodInOption for crate::$nonany<NICHE> {}
+
+        // SAFETY: `NonAny*` is `repr(transparent)` over `NonZero*`.
+        unsafe impl<const NICHE: $int> TransparentWrapper<core::num::$nonzero> for crate::$nonany<NICHE> {}
+    };
+}
+
+nonany_bytemuck!(NonAnyI8, NonZeroI8, i8);
+nonany_bytemuck!(NonAnyI16, NonZeroI16, i16);
+nonany_bytemuck!(NonAnyI32, NonZeroI32, i32);
+nonany_bytemuck!(NonAnyI64, NonZeroI64, i64);
+nonany_bytemuck!(NonAnyI128, NonZeroI128, i128);
+nonany_bytemuck!(NonAnyIsize, NonZeroIsize, isize);
+
+nonany_bytemuck!(NonAnyU8, NonZeroU8, u8);
+nonany_bytemuck!(NonAnyU16, NonZeroU16, u16);
+nonany_bytemuck!(NonAnyU32, NonZeroU32, u32);
+nonany_bytemuck!(NonAnyU64, NonZeroU64, u64);
+nonany_bytemuck!(NonAnyU128, NonZeroU128, u128);
+nonany_bytemuck!(NonAnyUsize, NonZeroUsize, usize);
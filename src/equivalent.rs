@@ -0,0 +1,57 @@
+//! [equivalent](https://docs.rs/equivalent) support for the `NonAny*` types, which is the `Equivalent` trait used by
+//! [hashbrown](https://docs.rs/hashbrown) and [indexmap](https://docs.rs/indexmap).
+//!
+//! A `NonAny*` hashes its value like the primitive integer, so a map keyed by a `NonAny*` can be looked up with the
+//! primitive integer. A `NonAny*` with a different niche can be used when wrapped in [`ByValue`], because an
+//! implementation for the type itself would conflict with the blanket implementation of the `equivalent` crate.
+//!
+//! ```rust
+//! use indexmap::IndexMap;
+//! use nonany::{NonAnyU32, NonMaxU32};
+//! use nonany::equivalent::ByValue;
+//!
+//! let mut map = IndexMap::new();
+//! map.insert(NonMaxU32::new(7).unwrap(), "seven");
+//!
+//! assert_eq!(map.get(&7u32), Some(&"seven"));
+//! assert_eq!(map.get(&ByValue(NonAnyU32::<5>::new(7).unwrap())), Some(&"seven"));
+//! assert_eq!(map.get(&u32::MAX), None);
+//! ```
+
+use ::equivalent::Equivalent;
+
+/// Wraps a `NonAny*` to look it up in a map that is keyed by the same integer type with a different niche.
+///
+/// It hashes like the wrapped value, and is equivalent to any key with the same value.
+#[derive(Clone, Copy, Debug, Hash)]
+pub struct ByValue<T>(pub T);
+
+macro_rules! nonany_equivalent {
+    ($nonany:ident, $int:ident) => {
+        impl<const NICHE: $int> Equivalent<crate::$nonany<NICHE>> for $int {
+            fn equivalent(&self, key: &crate::$nonany<NICHE>) -> bool {
+                *self == key.get()
+            }
+        }
+
+        impl<const NICHE: $int, const OTHER: $int> Equivalent<crate::$nonany<NICHE>> for ByValue<crate::$nonany<OTHER>> {
+            fn equivalent(&self, key: &crate::$nonany<NICHE>) -> bool {
+                self.0.get() == key.get()
+            }
+        }
+    };
+}
+
+nonany_equivalent!(NonAnyI8, i8);
+nonany_equivalent!(NonAnyI16, i16);
+nonany_equivalent!(NonAnyI32, i32);
+nonany_equivalent!(NonAnyI64, i64);
+nonany_equivalent!(NonAnyI128, i128);
+nonany_equivalent!(NonAnyIsize, isize);
+
+nonany_equivalent!(NonAnyU8, u8);
+nonany_equivalent!(NonAnyU16, u16);
+nonany_equivalent!(NonAnyU32, u32);
+nonany_equivalent!(NonAnyU64, u64);
+nonany_equivalent!(NonAnyU128, u128);
+nonany_equivalent!(NonAnyUsize, usize);
@@ -0,0 +1,149 @@
+//! Helpers for passing `NonAny*` types across FFI boundaries.
+//!
+//! An `Option<NonAny*<NICHE>>` has the same layout as the integer, but stores `value ^ NICHE` with zero as `None`, which
+//! C code will misread. A `Sentinel*<NICHE>` is a `repr(transparent)` integer that stores the value itself, and uses
+//! `NICHE` as the sentinel for `None`, which is how C APIs usually express a missing value. Use it in `extern "C"`
+//! signatures, and convert it to and from an `Option<NonAny*<NICHE>>` on the Rust side.
+//!
+//! ```rust
+//! use nonany::ffi::{NonNegOneI32, SentinelI32};
+//!
+//! extern "C" fn find(key: i32) -> SentinelI32<-1> {
+//!     SentinelI32::new(NonNegOneI32::new(key * 2).filter(|_| key > 0))
+//! }
+//!
+//! assert_eq!(find(3).to_raw(), 6);
+//! assert_eq!(find(3).get(), NonNegOneI32::new(6));
+//! assert_eq!(find(-3).to_raw(), -1);
+//! assert_eq!(find(-3).get(), None);
+//! ```
+//!
+//! The [`HEADER`] constant contains C macros that describe both encodings, for use in handwritten headers or to be
+//! written out by a build script.
+
+use crate::{NonAnyI8, NonAnyI16, NonAnyI32, NonAnyI64, NonAnyI128, NonAnyIsize};
+
+/// C macros that describe how the `NonAny*` and `Sentinel*` types are stored.
+///
+/// ```rust
+/// assert!(nonany::ffi::HEADER.contains("#define NONANY_ENCODE(value, niche) ((value) ^ (niche))"));
+/// ```
+pub const HEADER: &str = "\
+/* A NonAny<NICHE> stores `value ^ NICHE`, and an Option<NonAny<NICHE>> stores 0 for None. */
+#define NONANY_ENCODE(value, niche) ((value) ^ (niche))
+#define NONANY_DECODE(bits, niche) ((bits) ^ (niche))
+#define NONANY_IS_NONE(bits) ((bits) == 0)
+
+/* A Sentinel<NICHE> stores the value itself, and NICHE for None. Both convert with the same XOR. */
+#define NONANY_SENTINEL_IS_NONE(value, niche) ((value) == (niche))
+#define NONANY_SENTINEL_FROM_BITS(bits, niche) ((bits) ^ (niche))
+#define NONANY_SENTINEL_TO_BITS(value, niche) ((value) ^ (niche))
+";
+
+macro_rules! nonany_sentinel {
+    ($sentinel:ident, $nonany:ident, $int:ident) => {
+        nonany_abi_stable!($int,
+            #[doc = concat!("An `Option<", stringify!($nonany), "<NICHE>>` that stores the value itself, and `NICHE` for `None`.")]
+            ///
+            #[doc = concat!("This has the same layout as `", stringify!($int), "`, and can be used in `extern \"C\"` signatures.")]
+            #[derive(Clone, Copy, Eq, Hash, PartialEq)]
+            #[repr(transparent)]
+            pub struct $sentinel<const NICHE: $int>($int);
+        );
+
+        impl<const NICHE: $int> $sentinel<NICHE> {
+            /// The sentinel that represents `None`.
+            pub const NONE: Self = Self(NICHE);
+
+            /// Creates a sentinel from an optional non-any.
+            pub const fn new(value: Option<crate::$nonany<NICHE>>) -> Self {
+                match value {
+                    Some(value) => Self(value.get()),
+                    None => Self::NONE,
+                }
+            }
+
+            /// Creates a sentinel from the raw value, where `NICHE` is `None`.
+            pub const fn from_raw(value: $int) -> Self {
+                Self(value)
+            }
+
+            /// Returns the raw value, which is `NICHE` for `None`.
+            pub const fn to_raw(self) -> $int {
+                self.0
+            }
+
+            /// Returns the value as an optional non-any.
+            pub const fn get(self) -> Option<crate::$nonany<NICHE>> {
+                crate::$nonany::new(self.0)
+            }
+
+            /// Returns `true` if the sentinel is `NICHE`.
+            pub const fn is_none(self) -> bool {
+                self.0 == NICHE
+            }
+
+            /// Returns `true` if the sentinel is not `NICHE`.
+            pub const fn is_some(self) -> bool {
+                self.0 != NICHE
+            }
+        }
+
+        impl<const NICHE: $int> Default for $sentinel<NICHE> {
+            fn default() -> Self {
+                Self::NONE
+            }
+        }
+
+        impl<const NICHE: $int> From<Option<crate::$nonany<NICHE>>> for $sentinel<NICHE> {
+            fn from(value: Option<crate::$nonany<NICHE>>) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl<const NICHE: $int> From<crate::$nonany<NICHE>> for $sentinel<NICHE> {
+            fn from(value: crate::$nonany<NICHE>) -> Self {
+                Self::new(Some(value))
+            }
+        }
+
+        impl<const NICHE: $int> From<$sentinel<NICHE>> for Option<crate::$nonany<NICHE>> {
+            fn from(value: $sentinel<NICHE>) -> Self {
+                value.get()
+            }
+        }
+
+        impl<const NICHE: $int> core::fmt::Debug for $sentinel<NICHE> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.get(), f)
+            }
+        }
+    };
+}
+
+nonany_sentinel!(SentinelI8, NonAnyI8, i8);
+nonany_sentinel!(SentinelI16, NonAnyI16, i16);
+nonany_sentinel!(SentinelI32, NonAnyI32, i32);
+nonany_sentinel!(SentinelI64, NonAnyI64, i64);
+nonany_sentinel!(SentinelI128, NonAnyI128, i128);
+nonany_sentinel!(SentinelIsize, NonAnyIsize, isize);
+
+nonany_sentinel!(SentinelU8, NonAnyU8, u8);
+nonany_sentinel!(SentinelU16, NonAnyU16, u16);
+nonany_sentinel!(SentinelU32, NonAnyU32, u32);
+nonany_sentinel!(SentinelU64, NonAnyU64, u64);
+nonany_sentinel!(SentinelU128, NonAnyU128, u128);
+nonany_sentinel!(SentinelUsize, NonAnyUsize, usize);
+
+/// An integer that is known not to equal `-1`.
+pub type NonNegOneI8 = NonAnyI8<-1>;
+/// An integer that is known not to equal `-1`.
+pub type NonNegOneI16 = NonAnyI16<-1>;
+/// An integer that is known not to equal `-1`, such as a POSIX file descriptor.
+pub type NonNegOneI32 = NonAnyI32<-1>;
+/// An integer that is known not to equal `-1`.
+pub type NonNegOneI64 = NonAnyI64<-1>;
+/// An integer that is known not to equal `-1`.
+pub type NonNegOneI128 = NonAnyI128<-1>;
+/// An integer that is known not to equal `-1`.
+pub type NonNegOneIsize = NonAnyIsize<-1>;
@@ -0,0 +1,418 @@
+//! Fixed-point numbers with customizable niche values.
+//!
+//! A `NonAnyFixed*<FRAC, NICHE>` stores a number as an integer of `FRAC` fractional bits, in a `NonAny*` integer
+//! with the given niche. The niche is compared against the raw bits, so `NonMinFixedI32<16>` is a Q16.16 number
+//! that cannot store `i32::MIN` as its bits, and `Option<NonMinFixedI32<16>>` is the same size as an `i32`.
+//!
+//! ```rust
+//! use nonany::fixed::{NonMinFixedI32, RoundingMode};
+//!
+//! let value = NonMinFixedI32::<16>::from_f64(1.25, RoundingMode::Nearest).unwrap();
+//! assert_eq!(value.to_bits(), 0x1_4000);
+//! assert_eq!(value.to_string(), "1.25");
+//!
+//! let sum = value.checked_add(value).unwrap();
+//! assert_eq!(sum.to_f64(), 2.5);
+//! ```
+
+use core::fmt::{self, Write};
+use crate::CheckedError;
+
+/// The rounding mode used when converting a floating point number to a fixed-point number.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RoundingMode {
+    /// Rounds toward zero.
+    TowardZero,
+    /// Rounds toward negative infinity.
+    Down,
+    /// Rounds toward positive infinity.
+    Up,
+    /// Rounds to the nearest value, and ties away from zero.
+    Nearest,
+    /// Rounds to the nearest value, and ties to the value with an even least significant bit.
+    NearestEven,
+}
+
+/// An error returned when converting a floating point number to a fixed-point number.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FromFloatError {
+    /// The number is `NaN`.
+    NaN,
+    /// The rounded number is out of range, or its bits are the niche.
+    Checked(CheckedError),
+}
+
+impl fmt::Display for FromFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromFloatError::NaN => f.write_str("value is not a number"),
+            FromFloatError::Checked(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromFloatError {}
+
+impl From<CheckedError> for FromFloatError {
+    fn from(err: CheckedError) -> Self {
+        FromFloatError::Checked(err)
+    }
+}
+
+impl RoundingMode {
+    /// Rounds the value to an integer. Values that are too large to have a fractional part are returned as is.
+    fn round(self, value: f64) -> f64 {
+        // Every `f64` with a magnitude of at least 2^52 is an integer.
+        const LIMIT: f64 = 4503599627370496.0;
+        if !(value > -LIMIT && value < LIMIT) {
+            return value;
+        }
+
+        let truncated = value as i64;
+        let down = if (truncated as f64) > value { truncated - 1 } else { truncated };
+        let diff = value - down as f64;
+        let rounded = match self {
+            RoundingMode::TowardZero => truncated,
+            RoundingMode::Down => down,
+            RoundingMode::Up if diff > 0.0 => down + 1,
+            RoundingMode::Up => down,
+            RoundingMode::Nearest if diff > 0.5 || (diff == 0.5 && value > 0.0) => down + 1,
+            RoundingMode::NearestEven if diff > 0.5 || (diff == 0.5 && down & 1 != 0) => down + 1,
+            RoundingMode::Nearest | RoundingMode::NearestEven => down,
+        };
+        rounded as f64
+    }
+}
+
+/// Writes a fixed-point number in decimal, exactly or rounded half to even to the requested precision.
+fn fmt_fixed(f: &mut fmt::Formatter<'_>, negative: bool, magnitude: u128, frac: u32) -> fmt::Result {
+    const LEN: usize = 128;
+    let mask = if frac == 0 { 0 } else { u128::MAX >> (128 - frac) };
+    let mut int = magnitude >> frac;
+    let mut rest = magnitude & mask;
+
+    // Fractional digits, written from the start of the buffer.
+    let mut digits = [0u8; LEN];
+    let mut len = 0;
+    let precision = f.precision();
+    while len < LEN && (precision.map_or(rest != 0, |precision| len < precision)) {
+        rest *= 10;
+        digits[len] = (rest >> frac) as u8;
+        rest &= mask;
+        len += 1;
+    }
+
+    if precision.is_some() && rest != 0 {
+        let half = 1u128 << (frac - 1);
+        let odd = if len == 0 { int & 1 != 0 } else { digits[len - 1] & 1 != 0 };
+        if rest > half || (rest == half && odd) {
+            let mut index = len;
+            loop {
+                if index == 0 {
+                    int += 1;
+                    break;
+                }
+                index -= 1;
+                if digits[index] == 9 {
+                    digits[index] = 0;
+                } else {
+                    digits[index] += 1;
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut buffer = [0u8; 40 + 1 + LEN];
+    let mut index = 40;
+    loop {
+        index -= 1;
+        buffer[index] = b'0' + (int % 10) as u8;
+        int /= 10;
+        if int == 0 {
+            break;
+        }
+    }
+
+    let start = index;
+    let mut end = 40;
+    if len != 0 {
+        buffer[end] = b'.';
+        end += 1;
+        for digit in &digits[..len] {
+            buffer[end] = b'0' + digit;
+            end += 1;
+        }
+    }
+
+    // SAFETY: Only ASCII characters have been written to this part of the buffer.
+    let text = unsafe { core::str::from_utf8_unchecked(&buffer[start..end]) };
+
+    // Digits beyond the buffer are always zero, as the exact expansion has at most `frac` digits.
+    let zeros = precision.map_or(0, |precision| precision.saturating_sub(len));
+    if zeros == 0 {
+        return f.pad_integral(!negative, "", text);
+    }
+
+    // The trailing zeros do not fit in the buffer, so the padding of `pad_integral` is applied by hand.
+    let sign = if negative { "-" } else if f.sign_plus() { "+" } else { "" };
+    let padding = f.width().map_or(0, |width| width.saturating_sub(sign.len() + text.len() + zeros));
+    let write_zeros = |f: &mut fmt::Formatter<'_>, count: usize| (0..count).try_for_each(|_| f.write_str("0"));
+    if f.sign_aware_zero_pad() {
+        f.write_str(sign)?;
+        write_zeros(f, padding)?;
+        f.write_str(text)?;
+        return write_zeros(f, zeros);
+    }
+
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Left) => (0, padding),
+        Some(fmt::Alignment::Center) => (padding / 2, (padding + 1) / 2),
+        _ => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(sign)?;
+    f.write_str(text)?;
+    write_zeros(f, zeros)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+macro_rules! nonany_fixed {
+    ($name:ident, $nonmin:ident, $nonmax:ident, $nonzero:ident, $nonany:ident, $int:ident, $wide:ident) => {
+        /// A fixed-point number with `FRAC` fractional bits, whose bits are known not to equal `NICHE`.
+        #[doc = concat!("For example, `Option<nonany::fixed::", stringify!($name), "<8, 0>>` is the same size as `", stringify!($int), "`:")]
+        ///
+        /// ```rust
+        #[doc = concat!("assert_eq!(core::mem::size_of::<Option<nonany::fixed::", stringify!($name), "<8, 0>>>(), core::mem::size_of::<", stringify!($int), ">());")]
+        /// ```
+        #[derive(Clone, Copy, Eq, Hash, PartialEq)]
+        #[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+        #[repr(transparent)]
+        pub struct $name<const FRAC: u32, const NICHE: $int>(crate::$nonany<NICHE>);
+
+        impl<const FRAC: u32, const NICHE: $int> $name<FRAC, NICHE> {
+            /// The number of fractional bits.
+            pub const FRAC: u32 = FRAC;
+
+            /// The niche value of the bits of this number.
+            pub const NICHE: $int = NICHE;
+
+            /// The value of the bits that represent one, which fails to compile if `FRAC` is larger than the number of bits.
+            #[allow(unconditional_panic, clippy::out_of_bounds_indexing)]
+            const ONE: $wide = [1 << FRAC][(FRAC > <$int>::BITS) as usize];
+
+            /// The smallest bits that can be stored.
+            const MIN_BITS: $int = if NICHE == <$int>::MIN { <$int>::MIN + 1 } else { <$int>::MIN };
+
+            /// The largest bits that can be stored.
+            const MAX_BITS: $int = if NICHE == <$int>::MAX { <$int>::MAX - 1 } else { <$int>::MAX };
+
+            /// Creates a fixed-point number from its raw bits, if the bits are not `NICHE`.
+            pub const fn from_bits(bits: $int) -> Option<Self> {
+                match crate::$nonany::new(bits) {
+                    Some(bits) => Some(Self(bits)),
+                    None => None
+                }
+            }
+
+            /// Returns the raw bits of the number.
+            pub const fn to_bits(self) -> $int {
+                self.0.get()
+            }
+
+            /// Creates a fixed-point number from a non-any holding its raw bits.
+            pub const fn from_nonany(bits: crate::$nonany<NICHE>) -> Self {
+                Self(bits)
+            }
+
+            /// Returns the raw bits of the number as a non-any.
+            pub const fn to_nonany(self) -> crate::$nonany<NICHE> {
+                self.0
+            }
+
+            /// Converts an integer to a fixed-point number.
+            pub fn from_int(value: $int) -> Result<Self, CheckedError> {
+                Self::from_wide(value as $wide * Self::ONE)
+            }
+
+            /// Converts a floating point number to a fixed-point number, rounding it with the given mode.
+            ///
+            /// `NaN` results in a [`FromFloatError::NaN`] error.
+            pub fn from_f64(value: f64, rounding: RoundingMode) -> Result<Self, FromFloatError> {
+                if value.is_nan() {
+                    return Err(FromFloatError::NaN);
+                }
+
+                // Scaling by a power of two is exact, unless it overflows to infinity.
+                let bits = rounding.round(value * Self::ONE as f64);
+                if bits < <$int>::MIN as f64 {
+                    Err(CheckedError::Underflow.into())
+                } else if bits >= <$int>::MAX as f64 + 1.0 {
+                    Err(CheckedError::Overflow.into())
+                } else {
+                    Self::from_bits(bits as $int).ok_or(CheckedError::Niche.into())
+                }
+            }
+
+            /// Converts a floating point number to a fixed-point number, rounding it with the given mode.
+            ///
+            /// `NaN` results in a [`FromFloatError::NaN`] error.
+            pub fn from_f32(value: f32, rounding: RoundingMode) -> Result<Self, FromFloatError> {
+                Self::from_f64(value as f64, rounding)
+            }
+
+            /// Converts the number to the nearest `f64`.
+            pub fn to_f64(self) -> f64 {
+                self.to_bits() as f64 / Self::ONE as f64
+            }
+
+            /// Converts the number to the nearest `f32`.
+            pub fn to_f32(self) -> f32 {
+                self.to_bits() as f32 / Self::ONE as f32
+            }
+
+            /// Adds two numbers, returning an error if the result overflows or is the niche.
+            pub fn checked_add(self, rhs: Self) -> Result<Self, CheckedError> {
+                Self::from_wide(self.to_bits() as $wide + rhs.to_bits() as $wide)
+            }
+
+            /// Subtracts two numbers, returning an error if the result overflows or is the niche.
+            pub fn checked_sub(self, rhs: Self) -> Result<Self, CheckedError> {
+                match (self.to_bits() as $wide).checked_sub(rhs.to_bits() as $wide) {
+                    Some(bits) => Self::from_wide(bits),
+                    None => Err(CheckedError::Underflow)
+                }
+            }
+
+            /// Multiplies two numbers, returning an error if the result overflows or is the niche.
+            ///
+            /// The result is rounded toward negative infinity.
+            pub fn checked_mul(self, rhs: Self) -> Result<Self, CheckedError> {
+                Self::from_wide(Self::mul_wide(self, rhs))
+            }
+
+            /// Divides two numbers, returning an error if the result overflows or is the niche.
+            ///
+            /// The result is rounded toward zero. Dividing by zero results in a [`CheckedError::Overflow`] error.
+            pub fn checked_div(self, rhs: Self) -> Result<Self, CheckedError> {
+                match (self.to_bits() as $wide * Self::ONE).checked_div(rhs.to_bits() as $wide) {
+                    Some(bits) => Self::from_wide(bits),
+                    None => Err(CheckedError::Overflow)
+                }
+            }
+
+            /// Adds two numbers, saturating at the smallest and largest numbers that are not the niche.
+            ///
+            /// A result that is exactly the niche is rounded up to the next number.
+            pub fn saturating_add(self, rhs: Self) -> Self {
+                Self::from_wide_saturating(self.to_bits() as $wide + rhs.to_bits() as $wide)
+            }
+
+            /// Subtracts two numbers, saturating at the smallest and largest numbers that are not the niche.
+            ///
+            /// A result that is exactly the niche is rounded up to the next number.
+            pub fn saturating_sub(self, rhs: Self) -> Self {
+                match (self.to_bits() as $wide).checked_sub(rhs.to_bits() as $wide) {
+                    Some(bits) => Self::from_wide_saturating(bits),
+                    None => Self::from_wide_saturating(Self::MIN_BITS as $wide)
+                }
+            }
+
+            /// Multiplies two numbers, saturating at the smallest and largest numbers that are not the niche.
+            ///
+            /// A result that is exactly the niche is rounded up to the next number.
+            pub fn saturating_mul(self, rhs: Self) -> Self {
+                Self::from_wide_saturating(Self::mul_wide(self, rhs))
+            }
+
+            fn mul_wide(self, rhs: Self) -> $wide {
+                // The product of two values fits in the wide type, and shifting right rounds toward negative infinity.
+                (self.to_bits() as $wide * rhs.to_bits() as $wide) >> FRAC
+            }
+
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            fn from_wide(bits: $wide) -> Result<Self, CheckedError> {
+                if bits < <$int>::MIN as $wide {
+                    Err(CheckedError::Underflow)
+                } else if bits > <$int>::MAX as $wide {
+                    Err(CheckedError::Overflow)
+                } else {
+                    Self::from_bits(bits as $int).ok_or(CheckedError::Niche)
+                }
+            }
+
+            fn from_wide_saturating(bits: $wide) -> Self {
+                let bits = if bits < Self::MIN_BITS as $wide {
+                    Self::MIN_BITS
+                } else if bits > Self::MAX_BITS as $wide {
+                    Self::MAX_BITS
+                } else if bits == NICHE as $wide {
+                    // The niche is neither `MIN` nor `MAX` here, as those are excluded from the range above.
+                    NICHE + 1
+                } else {
+                    bits as $int
+                };
+                // SAFETY: The bits are in range and are not the niche.
+                Self(unsafe { crate::$nonany::new_unchecked(bits) })
+            }
+        }
+
+        impl<const FRAC: u32, const NICHE: $int> core::cmp::PartialOrd for $name<FRAC, NICHE> {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<const FRAC: u32, const NICHE: $int> core::cmp::Ord for $name<FRAC, NICHE> {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.to_bits().cmp(&other.to_bits())
+            }
+        }
+
+        impl<const FRAC: u32, const NICHE: $int> fmt::Debug for $name<FRAC, NICHE> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("value", &format_args!("{}", self))
+                    .field("bits", &self.to_bits())
+                    .field("frac", &FRAC)
+                    .finish()
+            }
+        }
+
+        impl<const FRAC: u32, const NICHE: $int> fmt::Display for $name<FRAC, NICHE> {
+            /// Writes the exact decimal value, or the value rounded half to even if a precision is given.
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                // Fails to compile if `FRAC` is larger than the number of bits.
+                let _ = Self::ONE;
+                let bits = self.to_bits();
+                let magnitude = (bits as $wide as i128).unsigned_abs();
+                fmt_fixed(f, bits < 0, magnitude, FRAC)
+            }
+        }
+
+        /// A fixed-point number with `FRAC` fractional bits, whose bits are known not to equal zero.
+        pub type $nonzero<const FRAC: u32> = $name<FRAC, 0>;
+
+        #[doc = concat!("A fixed-point number with `FRAC` fractional bits, whose bits are known not to equal `", stringify!($int), "::MIN`.")]
+        pub type $nonmin<const FRAC: u32> = $name<FRAC, { $int::MIN }>;
+
+        #[doc = concat!("A fixed-point number with `FRAC` fractional bits, whose bits are known not to equal `", stringify!($int), "::MAX`.")]
+        pub type $nonmax<const FRAC: u32> = $name<FRAC, { $int::MAX }>;
+    };
+}
+
+nonany_fixed!(NonAnyFixedI16, NonMinFixedI16, NonMaxFixedI16, NonZeroFixedI16, NonAnyI16, i16, i128);
+nonany_fixed!(NonAnyFixedI32, NonMinFixedI32, NonMaxFixedI32, NonZeroFixedI32, NonAnyI32, i32, i128);
+nonany_fixed!(NonAnyFixedI64, NonMinFixedI64, NonMaxFixedI64, NonZeroFixedI64, NonAnyI64, i64, i128);
+
+nonany_fixed!(NonAnyFixedU16, NonMinFixedU16, NonMaxFixedU16, NonZeroFixedU16, NonAnyU16, u16, u128);
+nonany_fixed!(NonAnyFixedU32, NonMinFixedU32, NonMaxFixedU32, NonZeroFixedU32, NonAnyU32, u32, u128);
+nonany_fixed!(NonAnyFixedU64, NonMinFixedU64, NonMaxFixedU64, NonZeroFixedU64, NonAnyU64, u64, u128);
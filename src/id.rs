@@ -0,0 +1,158 @@
+//! Lock-free allocators for identifiers that are never the niche.
+//!
+//! An `IdAllocator*<NICHE>` hands out increasing identifiers of a `NonAny*<NICHE>` type, skipping the niche, so that an
+//! `Option` of an identifier is the same size as the integer. When every identifier has been handed out, allocating
+//! returns a [`CheckedError::Overflow`] error.
+//!
+//! ```rust
+//! use nonany::id::IdAllocatorNonZeroU32;
+//!
+//! static IDS: IdAllocatorNonZeroU32 = IdAllocatorNonZeroU32::new();
+//!
+//! assert_eq!(IDS.allocate().unwrap().get(), 1);
+//! assert_eq!(IDS.allocate().unwrap().get(), 2);
+//! ```
+//!
+//! The `FREE` parameter sets the capacity of a free list, which recycles identifiers that are passed to
+//! [`free`](IdAllocatorU32::free). It defaults to zero, which disables recycling. The free list is an array of atomic
+//! slots that is scanned by allocations while it is not empty, so it is meant to be small. Recycled identifiers are
+//! handed out before new ones, so identifiers are only increasing when the free list is not used.
+//!
+//! ```rust
+//! use nonany::id::IdAllocatorU64;
+//!
+//! let ids = IdAllocatorU64::<{ u64::MAX }, 4>::new();
+//! let first = ids.allocate().unwrap();
+//! assert_eq!(first.get(), 0);
+//! assert_eq!(ids.allocate().unwrap().get(), 1);
+//!
+//! ids.free(first).unwrap();
+//! assert_eq!(ids.allocate().unwrap(), first);
+//! assert_eq!(ids.allocate().unwrap().get(), 2);
+//! ```
+
+use core::sync::atomic::Ordering;
+use crate::CheckedError;
+
+macro_rules! nonany_id {
+    ($allocator:ident, $option:ident, $atomic:ident, $nonany:ident, $int:ident, $nonzero:ident, $nonmin:ident, $nonmax:ident) => {
+        #[doc = concat!("A lock-free allocator of [`", stringify!($nonany), "`](crate::", stringify!($nonany), ") identifiers, with a free list of `FREE` identifiers.")]
+        pub struct $allocator<const NICHE: $int, const FREE: usize = 0> {
+            next: crate::atomic::$option<NICHE>,
+            free: [crate::atomic::$option<NICHE>; FREE],
+            // An upper bound on the number of identifiers on the free list, so that allocations skip the scan while
+            // it is empty. It is raised before an identifier is stored, and lowered after one is taken.
+            free_len: crate::atomic::imp::$atomic,
+        }
+
+        impl<const NICHE: $int, const FREE: usize> $allocator<NICHE, FREE> {
+            // Only used as the repeated element of the free list, which cannot be `Copy`.
+            #[allow(clippy::declare_interior_mutable_const)]
+            const NONE: crate::atomic::$option<NICHE> = crate::atomic::$option::none();
+
+            /// Creates an allocator that starts at zero, or at one if the niche is zero.
+            pub const fn new() -> Self {
+                match crate::$nonany::new(0) {
+                    Some(start) => Self::with_start(start),
+                    // SAFETY: One is not the niche if zero is.
+                    None => Self::with_start(unsafe { crate::$nonany::new_unchecked(1) }),
+                }
+            }
+
+            /// Creates an allocator that starts at the given identifier.
+            pub const fn with_start(start: crate::$nonany<NICHE>) -> Self {
+                Self {
+                    next: crate::atomic::$option::new(Some(start)),
+                    free: [Self::NONE; FREE],
+                    free_len: crate::atomic::imp::$atomic::new(0),
+                }
+            }
+
+            /// Allocates an identifier, or returns a [`CheckedError::Overflow`] error if they have all been handed out.
+            ///
+            /// Identifiers on the free list are handed out first.
+            pub fn allocate(&self) -> Result<crate::$nonany<NICHE>, CheckedError> {
+                if self.free_len.load(Ordering::Relaxed) != 0 {
+                    for slot in self.free.iter() {
+                        if slot.load(Ordering::Relaxed).is_some() {
+                            if let Some(id) = slot.take(Ordering::Acquire) {
+                                self.free_len.fetch_sub(1, Ordering::Relaxed);
+                                return Ok(id);
+                            }
+                        }
+                    }
+                }
+
+                match self.next.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |next| next.map(Self::successor)) {
+                    Ok(Some(id)) => Ok(id),
+                    Ok(None) | Err(_) => Err(CheckedError::Overflow),
+                }
+            }
+
+            /// Puts an identifier on the free list, so that it is handed out again by the next allocation.
+            ///
+            /// Returns the identifier back as an error if the free list is full. Freeing an identifier that is not
+            /// allocated, or freeing it twice, results in the identifier being handed out more than once. Debug builds
+            /// panic when freeing an identifier that has not been handed out yet.
+            pub fn free(&self, id: crate::$nonany<NICHE>) -> Result<(), crate::$nonany<NICHE>> {
+                debug_assert!(
+                    self.peek().map_or(true, |next| id.get() < next.get()),
+                    "freed an identifier that has not been allocated",
+                );
+
+                self.free_len.fetch_add(1, Ordering::Relaxed);
+                for slot in self.free.iter() {
+                    if slot.compare_exchange(None, Some(id), Ordering::Release, Ordering::Relaxed).is_ok() {
+                        return Ok(());
+                    }
+                }
+                self.free_len.fetch_sub(1, Ordering::Relaxed);
+                Err(id)
+            }
+
+            /// Returns the identifier that will be handed out after the free list is empty, or `None` if they have
+            /// all been handed out.
+            pub fn peek(&self) -> Option<crate::$nonany<NICHE>> {
+                self.next.load(Ordering::Relaxed)
+            }
+
+            fn successor(id: crate::$nonany<NICHE>) -> Option<crate::$nonany<NICHE>> {
+                match id.get().checked_add(1) {
+                    Some(next) if next == NICHE => next.checked_add(1).and_then(crate::$nonany::new),
+                    Some(next) => crate::$nonany::new(next),
+                    None => None,
+                }
+            }
+        }
+
+        impl<const NICHE: $int, const FREE: usize> Default for $allocator<NICHE, FREE> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<const NICHE: $int, const FREE: usize> core::fmt::Debug for $allocator<NICHE, FREE> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($allocator))
+                    .field("next", &self.peek())
+                    .finish_non_exhaustive()
+            }
+        }
+
+        /// An allocator of identifiers that are known not to equal zero.
+        pub type $nonzero<const FREE: usize = 0> = $allocator<0, FREE>;
+
+        #[doc = concat!("An allocator of identifiers that are known not to equal `", stringify!($int), "::MIN`.")]
+        pub type $nonmin<const FREE: usize = 0> = $allocator<{ $int::MIN }, FREE>;
+
+        #[doc = concat!("An allocator of identifiers that are known not to equal `", stringify!($int), "::MAX`.")]
+        pub type $nonmax<const FREE: usize = 0> = $allocator<{ $int::MAX }, FREE>;
+    };
+}
+
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "32"))]
+nonany_id!(IdAllocatorU32, AtomicOptionNonAnyU32, AtomicU32, NonAnyU32, u32, IdAllocatorNonZeroU32, IdAllocatorNonMinU32, IdAllocatorNonMaxU32);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "64"))]
+nonany_id!(IdAllocatorU64, AtomicOptionNonAnyU64, AtomicU64, NonAnyU64, u64, IdAllocatorNonZeroU64, IdAllocatorNonMinU64, IdAllocatorNonMaxU64);
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "ptr"))]
+nonany_id!(IdAllocatorUsize, AtomicOptionNonAnyUsize, AtomicUsize, NonAnyUsize, usize, IdAllocatorNonZeroUsize, IdAllocatorNonMinUsize, IdAllocatorNonMaxUsize);
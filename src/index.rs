@@ -0,0 +1,492 @@
+//! Vectors that are indexed by typed `NonMax*` indices.
+//!
+//! The [`define_index!`](crate::define_index) macro defines an index type that wraps a [`NonMaxU32`](crate::NonMaxU32)
+//! or a [`NonMaxUsize`](crate::NonMaxUsize), so that an `Option` of the index is the same size as the index. An
+//! [`IndexVec`] and an [`IndexSlice`] can only be indexed by the index type they are declared with, which prevents
+//! mixing up indices into different vectors.
+//!
+//! ```rust
+//! use nonany::index::IndexVec;
+//!
+//! nonany::define_index! {
+//!     /// The index of a node in a graph.
+//!     pub struct NodeId(NonMaxU32);
+//! }
+//!
+//! struct Node {
+//!     name: &'static str,
+//!     parent: Option<NodeId>,
+//! }
+//!
+//! let mut nodes = IndexVec::<NodeId, Node>::new();
+//! let root = nodes.push(Node { name: "root", parent: None });
+//! let child = nodes.push(Node { name: "child", parent: Some(root) });
+//!
+//! assert_eq!(nodes[child].parent, Some(root));
+//! assert_eq!(nodes[root].name, "root");
+//! assert_eq!(core::mem::size_of::<Option<NodeId>>(), core::mem::size_of::<u32>());
+//!
+//! let names: Vec<_> = nodes.iter_enumerated().map(|(id, node)| (id.index(), node.name)).collect();
+//! assert_eq!(names, [(0, "root"), (1, "child")]);
+//! ```
+
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, Index, IndexMut};
+
+/// A typed index into an [`IndexVec`] or an [`IndexSlice`].
+///
+/// This is implemented by the types defined with [`define_index!`](crate::define_index).
+pub trait Idx: Copy + Eq + Ord + Hash + fmt::Debug {
+    /// Creates an index from a `usize`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is too large for the index type.
+    fn new(index: usize) -> Self;
+
+    /// Returns the index as a `usize`.
+    fn index(self) -> usize;
+}
+
+/// Defines an index type that wraps a `NonMaxU32` or a `NonMaxUsize`, and implements [`Idx`](crate::index::Idx) for it.
+///
+/// ```rust
+/// nonany::define_index! {
+///     /// The index of an edge in a graph.
+///     pub struct EdgeId(NonMaxUsize);
+/// }
+///
+/// let edge = EdgeId::new(5);
+/// assert_eq!(edge.index(), 5);
+/// assert_eq!(edge.get(), 5);
+/// assert_eq!(EdgeId::try_from_usize(usize::MAX), None);
+/// assert_eq!(format!("{:?}", edge), "EdgeId(5)");
+/// ```
+#[macro_export]
+macro_rules! define_index {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident(NonMaxU32);) => {
+        $crate::define_index!(@define, $(#[$attr])* $vis $name, NonMaxU32, u32);
+    };
+
+    ($(#[$attr:meta])* $vis:vis struct $name:ident(NonMaxUsize);) => {
+        $crate::define_index!(@define, $(#[$attr])* $vis $name, NonMaxUsize, usize);
+    };
+
+    (@define, $(#[$attr:meta])* $vis:vis $name:ident, $nonmax:ident, $int:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        $vis struct $name($crate::$nonmax);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// The largest index.
+            pub const MAX: Self = Self($crate::$nonmax::new_const::<{ <$int>::MAX - 1 }>());
+
+            /// Creates an index from a `usize`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the index is larger than `MAX`.
+            pub fn new(index: usize) -> Self {
+                match Self::try_from_usize(index) {
+                    Some(index) => index,
+                    None => panic!(concat!("index is too large for ", stringify!($name))),
+                }
+            }
+
+            /// Creates an index from a `usize`, or returns `None` if the index is larger than `MAX`.
+            pub fn try_from_usize(index: usize) -> Option<Self> {
+                match core::convert::TryFrom::try_from(index) {
+                    Ok(index) => Some(Self(index)),
+                    Err(_) => None,
+                }
+            }
+
+            /// Creates an index from the non-max integer.
+            pub const fn from_nonmax(index: $crate::$nonmax) -> Self {
+                Self(index)
+            }
+
+            /// Returns the index as a non-max integer.
+            pub const fn to_nonmax(self) -> $crate::$nonmax {
+                self.0
+            }
+
+            /// Returns the index as a primitive integer.
+            pub const fn get(self) -> $int {
+                self.0.get()
+            }
+
+            /// Returns the index as a `usize`.
+            pub const fn index(self) -> usize {
+                self.0.get() as usize
+            }
+        }
+
+        impl $crate::index::Idx for $name {
+            fn new(index: usize) -> Self {
+                Self::new(index)
+            }
+
+            fn index(self) -> usize {
+                self.index()
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, concat!(stringify!($name), "({})"), self.0)
+            }
+        }
+
+        impl core::convert::From<$name> for usize {
+            fn from(index: $name) -> Self {
+                index.index()
+            }
+        }
+    };
+}
+
+/// A slice that can only be indexed by `I`.
+#[repr(transparent)]
+pub struct IndexSlice<I: Idx, T> {
+    _marker: PhantomData<fn(&I)>,
+    raw: [T],
+}
+
+impl<I: Idx, T> IndexSlice<I, T> {
+    /// Wraps a slice.
+    pub fn from_raw(raw: &[T]) -> &Self {
+        // SAFETY: `IndexSlice` is `repr(transparent)` over the slice.
+        unsafe { &*(raw as *const [T] as *const Self) }
+    }
+
+    /// Wraps a mutable slice.
+    pub fn from_raw_mut(raw: &mut [T]) -> &mut Self {
+        // SAFETY: `IndexSlice` is `repr(transparent)` over the slice.
+        unsafe { &mut *(raw as *mut [T] as *mut Self) }
+    }
+
+    /// Returns the underlying slice.
+    pub fn as_raw(&self) -> &[T] {
+        &self.raw
+    }
+
+    /// Returns the underlying mutable slice.
+    pub fn as_raw_mut(&mut self) -> &mut [T] {
+        &mut self.raw
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.raw.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+
+    /// Returns the index that the next element pushed to the vector will get.
+    pub fn next_index(&self) -> I {
+        I::new(self.raw.len())
+    }
+
+    /// Returns a reference to the element at the index, or `None` if it is out of bounds.
+    pub fn get(&self, index: I) -> Option<&T> {
+        self.raw.get(index.index())
+    }
+
+    /// Returns a mutable reference to the element at the index, or `None` if it is out of bounds.
+    pub fn get_mut(&mut self, index: I) -> Option<&mut T> {
+        self.raw.get_mut(index.index())
+    }
+
+    /// Swaps the elements at the two indices.
+    pub fn swap(&mut self, a: I, b: I) {
+        self.raw.swap(a.index(), b.index())
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.raw.iter()
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.raw.iter_mut()
+    }
+
+    /// Returns an iterator over the indices and the elements.
+    pub fn iter_enumerated(&self) -> impl DoubleEndedIterator<Item = (I, &T)> + ExactSizeIterator + '_ {
+        self.raw.iter().enumerate().map(|(index, value)| (I::new(index), value))
+    }
+
+    /// Returns an iterator over the indices and mutable references to the elements.
+    pub fn iter_enumerated_mut(&mut self) -> impl DoubleEndedIterator<Item = (I, &mut T)> + ExactSizeIterator + '_ {
+        self.raw.iter_mut().enumerate().map(|(index, value)| (I::new(index), value))
+    }
+
+    /// Returns an iterator over the indices.
+    pub fn indices(&self) -> impl DoubleEndedIterator<Item = I> + ExactSizeIterator + '_ {
+        (0..self.raw.len()).map(I::new)
+    }
+}
+
+impl<I: Idx, T> Index<I> for IndexSlice<I, T> {
+    type Output = T;
+
+    fn index(&self, index: I) -> &T {
+        &self.raw[index.index()]
+    }
+}
+
+impl<I: Idx, T> IndexMut<I> for IndexSlice<I, T> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.raw[index.index()]
+    }
+}
+
+impl<'a, I: Idx, T> IntoIterator for &'a IndexSlice<I, T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.raw.iter()
+    }
+}
+
+impl<'a, I: Idx, T> IntoIterator for &'a mut IndexSlice<I, T> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.raw.iter_mut()
+    }
+}
+
+impl<I: Idx, T: PartialEq> PartialEq for IndexSlice<I, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<I: Idx, T: Eq> Eq for IndexSlice<I, T> {}
+
+impl<I: Idx, T: Hash> Hash for IndexSlice<I, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state)
+    }
+}
+
+impl<I: Idx, T: fmt::Debug> fmt::Debug for IndexSlice<I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.raw, f)
+    }
+}
+
+/// A vector that can only be indexed by `I`.
+pub struct IndexVec<I: Idx, T> {
+    _marker: PhantomData<fn(&I)>,
+    raw: Vec<T>,
+}
+
+impl<I: Idx, T> IndexVec<I, T> {
+    /// Creates an empty vector.
+    pub fn new() -> Self {
+        Self::from_raw(Vec::new())
+    }
+
+    /// Creates an empty vector with space for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_raw(Vec::with_capacity(capacity))
+    }
+
+    /// Wraps a vector.
+    pub fn from_raw(raw: Vec<T>) -> Self {
+        Self { _marker: PhantomData, raw }
+    }
+
+    /// Returns the underlying vector.
+    pub fn into_raw(self) -> Vec<T> {
+        self.raw
+    }
+
+    /// Returns the vector as an index slice.
+    pub fn as_slice(&self) -> &IndexSlice<I, T> {
+        IndexSlice::from_raw(&self.raw)
+    }
+
+    /// Returns the vector as a mutable index slice.
+    pub fn as_mut_slice(&mut self) -> &mut IndexSlice<I, T> {
+        IndexSlice::from_raw_mut(&mut self.raw)
+    }
+
+    /// Appends an element, and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of the element is too large for the index type.
+    pub fn push(&mut self, value: T) -> I {
+        let index = I::new(self.raw.len());
+        self.raw.push(value);
+        index
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.raw.pop()
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.raw.capacity()
+    }
+
+    /// Reserves space for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        self.raw.reserve(additional)
+    }
+
+    /// Shortens the vector to `len` elements.
+    pub fn truncate(&mut self, len: usize) {
+        self.raw.truncate(len)
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.raw.clear()
+    }
+
+    /// Resizes the vector such that `index` is in bounds, filling new elements with the given function.
+    pub fn ensure_contains_elem(&mut self, index: I, fill: impl FnMut() -> T) -> &mut T {
+        let min_len = index.index() + 1;
+        if self.raw.len() < min_len {
+            self.raw.resize_with(min_len, fill);
+        }
+        &mut self.raw[index.index()]
+    }
+}
+
+impl<I: Idx, T> Deref for IndexVec<I, T> {
+    type Target = IndexSlice<I, T>;
+
+    fn deref(&self) -> &IndexSlice<I, T> {
+        self.as_slice()
+    }
+}
+
+impl<I: Idx, T> DerefMut for IndexVec<I, T> {
+    fn deref_mut(&mut self) -> &mut IndexSlice<I, T> {
+        self.as_mut_slice()
+    }
+}
+
+impl<I: Idx, T> Borrow<IndexSlice<I, T>> for IndexVec<I, T> {
+    fn borrow(&self) -> &IndexSlice<I, T> {
+        self.as_slice()
+    }
+}
+
+impl<I: Idx, T> BorrowMut<IndexSlice<I, T>> for IndexVec<I, T> {
+    fn borrow_mut(&mut self) -> &mut IndexSlice<I, T> {
+        self.as_mut_slice()
+    }
+}
+
+impl<I: Idx, T> Index<I> for IndexVec<I, T> {
+    type Output = T;
+
+    fn index(&self, index: I) -> &T {
+        &self.raw[index.index()]
+    }
+}
+
+impl<I: Idx, T> IndexMut<I> for IndexVec<I, T> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.raw[index.index()]
+    }
+}
+
+impl<I: Idx, T> Default for IndexVec<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Idx, T: Clone> Clone for IndexVec<I, T> {
+    fn clone(&self) -> Self {
+        Self::from_raw(self.raw.clone())
+    }
+}
+
+impl<I: Idx, T: PartialEq> PartialEq for IndexVec<I, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<I: Idx, T: Eq> Eq for IndexVec<I, T> {}
+
+impl<I: Idx, T: Hash> Hash for IndexVec<I, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state)
+    }
+}
+
+impl<I: Idx, T: fmt::Debug> fmt::Debug for IndexVec<I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.raw, f)
+    }
+}
+
+impl<I: Idx, T> FromIterator<T> for IndexVec<I, T> {
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        Self::from_raw(Vec::from_iter(iter))
+    }
+}
+
+impl<I: Idx, T> Extend<T> for IndexVec<I, T> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.raw.extend(iter)
+    }
+}
+
+impl<I: Idx, T> IntoIterator for IndexVec<I, T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.raw.into_iter()
+    }
+}
+
+impl<'a, I: Idx, T> IntoIterator for &'a IndexVec<I, T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.raw.iter()
+    }
+}
+
+impl<'a, I: Idx, T> IntoIterator for &'a mut IndexVec<I, T> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.raw.iter_mut()
+    }
+}
+
+impl<I: Idx, T> From<Vec<T>> for IndexVec<I, T> {
+    fn from(raw: Vec<T>) -> Self {
+        Self::from_raw(raw)
+    }
+}
@@ -1,26 +1,370 @@
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(pattern_types, pattern_type_macro, structural_match))]
+// The lint for internal features is emitted for the crate attribute above, so it cannot be allowed in the `nightly`
+// module alone.
+#![cfg_attr(feature = "nightly", allow(internal_features))]
 #![doc = include_str!("../README.md")]
 
-/// An error type used to differentiate between overflow and niche errors.
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "bytemuck")]
+pub mod bytemuck;
+
+#[cfg(feature = "equivalent")]
+pub mod equivalent;
+
+pub mod varint;
+
+/// An error type used to differentiate between overflow, underflow and niche errors.
+///
+/// More kinds of errors may be added in the future, so matching on this enum requires a wildcard arm. Adding
+/// `Underflow` and `#[non_exhaustive]` are breaking changes from 0.3.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
 pub enum CheckedError {
+    /// The value is larger than the maximum value of the target type.
     Overflow,
+    /// The value is smaller than the minimum value of the target type.
+    Underflow,
+    /// The value is equal to the niche of the target type.
+    Niche,
+}
+
+impl core::fmt::Display for CheckedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            CheckedError::Overflow => "value is too large to fit in the target type",
+            CheckedError::Underflow => "value is too small to fit in the target type",
+            CheckedError::Niche => "value is equal to the niche of the target type",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CheckedError {}
+
+/// Converts the error into [`CheckedError::Overflow`].
+///
+/// `TryFromIntError` does not tell whether the value was too large or too small, so this conversion always returns
+/// `Overflow`, even for a negative value that was converted to an unsigned type. The `TryFrom` implementations of the
+/// `NonAny*` types return `Underflow` for those, so convert with `try_from` or `try_from_with` on the `NonAny*` type
+/// instead of going through a primitive integer when the direction matters.
+impl From<core::num::TryFromIntError> for CheckedError {
+    fn from(_: core::num::TryFromIntError) -> Self {
+        CheckedError::Overflow
+    }
+}
+
+/// A [`CheckedError`] together with the value that failed to convert and the niche of the target type.
+///
+/// ```rust
+/// use nonany::{CheckedError, NonAnyU8};
+///
+/// let err = NonAnyU8::<7>::try_from_with(-3i64).unwrap_err();
+/// assert_eq!(err.error(), CheckedError::Underflow);
+/// assert_eq!(err.value(), -3);
+/// assert_eq!(err.niche(), 7);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CheckedErrorWith<T, N = T> {
+    error: CheckedError,
+    value: T,
+    niche: N,
+}
+
+impl<T, N> CheckedErrorWith<T, N> {
+    /// Creates an error with the value that failed to convert and the niche of the target type.
+    pub const fn new(error: CheckedError, value: T, niche: N) -> Self {
+        Self { error, value, niche }
+    }
+
+    /// Returns the kind of error.
+    pub const fn error(&self) -> CheckedError {
+        self.error
+    }
+
+    /// Returns the value that failed to convert.
+    pub fn value(&self) -> T where T: Copy {
+        self.value
+    }
+
+    /// Returns the niche of the target type.
+    pub fn niche(&self) -> N where N: Copy {
+        self.niche
+    }
+
+    /// Returns the value that failed to convert, consuming the error.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T, N> From<CheckedErrorWith<T, N>> for CheckedError {
+    fn from(error: CheckedErrorWith<T, N>) -> Self {
+        error.error
+    }
+}
+
+impl<T: core::fmt::Display, N: core::fmt::Display> core::fmt::Display for CheckedErrorWith<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (value: {}, niche: {})", self.error, self.value, self.niche)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::fmt::Debug + core::fmt::Display, N: core::fmt::Debug + core::fmt::Display> std::error::Error for CheckedErrorWith<T, N> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// An error which can be returned when parsing a non-any.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ParseNonAnyError {
+    kind: ParseNonAnyErrorKind,
+}
+
+impl ParseNonAnyError {
+    /// Outputs the detailed cause of parsing a non-any failing.
+    pub const fn kind(&self) -> &ParseNonAnyErrorKind {
+        &self.kind
+    }
+}
+
+impl core::fmt::Display for ParseNonAnyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self.kind {
+            ParseNonAnyErrorKind::Empty => "cannot parse integer from empty string",
+            ParseNonAnyErrorKind::InvalidDigit => "invalid digit found in string",
+            ParseNonAnyErrorKind::PosOverflow => "number too large to fit in target type",
+            ParseNonAnyErrorKind::NegOverflow => "number too small to fit in target type",
+            ParseNonAnyErrorKind::Niche => "number is equal to the niche of the target type",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseNonAnyError {}
+
+/// Enum to store the various types of errors that can cause parsing a non-any to fail.
+///
+/// This mirrors `core::num::IntErrorKind`, with an additional kind for values that are equal to the niche.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ParseNonAnyErrorKind {
+    /// Value being parsed is empty.
+    Empty,
+    /// Contains an invalid digit in its context, or the radix is not in the range from 2 to 36.
+    InvalidDigit,
+    /// Integer is too large to store in target integer type.
+    PosOverflow,
+    /// Integer is too small to store in target integer type.
+    NegOverflow,
+    /// Integer is equal to the niche of the target type.
     Niche,
 }
 
+mod sealed {
+    pub trait Sealed {}
+
+    /// A value of any width and signedness, ordered like the value.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Wide {
+        Negative(i128),
+        NonNegative(u128),
+    }
+
+    macro_rules! value {
+        ($($int:ident),*) => {
+            $(
+                impl Sealed for $int {}
+
+                impl super::Value for $int {
+                    #[allow(unused_comparisons)]
+                    fn wide(self) -> Wide {
+                        if self < 0 {
+                            Wide::Negative(self as i128)
+                        } else {
+                            Wide::NonNegative(self as u128)
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+    impl<T: super::NonAny> super::Value for T where T::Int: super::Value {
+        fn wide(self) -> Wide {
+            self.get().wide()
+        }
+    }
+}
+
+/// A primitive integer or a `NonAny*` type, whose value can be compared with the value of a non-any by its `value_eq`
+/// and `value_cmp` methods.
+///
+/// The non-anys compare with other integer types through these methods instead of `PartialEq` and `PartialOrd`
+/// implementations, because such implementations would make comparisons with integer literals ambiguous to type
+/// inference.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Value: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    fn wide(self) -> sealed::Wide;
+}
+
+/// A trait implemented by all `NonAny*` types, for code that is generic over them.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait NonAny: Copy + Eq + Ord + core::hash::Hash + core::fmt::Debug + sealed::Sealed {
+    /// The primitive integer type.
+    type Int: Copy + Eq + Ord + core::hash::Hash + core::fmt::Debug + core::fmt::Display;
+
+    /// The niche value of this integer type.
+    const NICHE: Self::Int;
+
+    /// Creates a non-any if the given value is not `NICHE`.
+    fn new(value: Self::Int) -> Option<Self>;
+
+    /// Creates a non-any without checking whether the value is `NICHE`.
+    ///
+    /// # Safety
+    ///
+    /// The value must not be `NICHE`.
+    unsafe fn new_unchecked(value: Self::Int) -> Self;
+
+    /// Returns the contained value as a primitive type.
+    fn get(self) -> Self::Int;
+}
+
+/// A fieldless enum that can be stored in a `NonAny*` integer, using a value that isn't one of its discriminants as the niche.
+///
+/// Implementing this trait with `#[derive(NonAnyRepr)]` requires the `derive` feature.
+/// The derive macro also implements `From<Self> for Self::NonAny` and `TryFrom<Self::NonAny> for Self`,
+/// where the error returned by `try_from` is the value that did not match any discriminant.
+///
+/// The niche is picked from the first value that isn't used by any of the discriminants, in the order
+/// `0`, `<int>::MAX`, `<int>::MIN`, `1`, `2`, `3`, etc. It can also be chosen explicitly with
+/// `#[nonany(niche = ...)]`, which is required when the discriminants are not integer literals.
+///
+/// ```rust
+/// # use nonany_derive::NonAnyRepr;
+/// #[derive(Clone, Copy, Debug, PartialEq, NonAnyRepr)]
+/// #[repr(u8)]
+/// enum Opcode {
+///     Nop,
+///     Load,
+///     Store,
+/// }
+///
+/// let opcode: Option<nonany::NonAnyU8<{ u8::MAX }>> = Some(Opcode::Load.into());
+/// assert_eq!(core::mem::size_of_val(&opcode), 1);
+/// assert_eq!(Opcode::try_from(opcode.unwrap()), Ok(Opcode::Load));
+/// ```
+///
+/// An explicit niche that collides with one of the discriminants results in a compile error:
+///
+/// ```compile_fail
+/// # use nonany_derive::NonAnyRepr;
+/// #[derive(NonAnyRepr)]
+/// #[nonany(niche = 1)]
+/// #[repr(u8)]
+/// enum Opcode {
+///     Nop,
+///     Load,
+/// }
+/// ```
+pub trait NonAnyRepr {
+    /// The `NonAny*` type this enum is stored in.
+    type NonAny;
+}
+
+#[cfg(feature = "derive")]
+pub use nonany_derive::NonAnyRepr;
+
+/// Creates a `NonAny*` from a constant, which fails to compile if the constant is the niche.
+///
+/// ```rust
+/// use nonany::{nonany_lit, NonMaxU32};
+///
+/// const ANSWER: NonMaxU32 = nonany_lit!(NonMaxU32, 42);
+/// assert_eq!(ANSWER.get(), 42);
+/// ```
+///
+/// ```compile_fail
+/// use nonany::{nonany_lit, NonMaxU32};
+///
+/// const ANSWER: NonMaxU32 = nonany_lit!(NonMaxU32, u32::MAX);
+/// ```
+#[macro_export]
+macro_rules! nonany_lit {
+    ($nonany:ty, $value:expr) => {
+        <$nonany>::new_const::<{ $value }>()
+    };
+}
+
+const fn decimal_len(max: u128, signed: bool) -> usize {
+    let mut len = 1;
+    let mut max = max / 10;
+    while max != 0 {
+        len += 1;
+        max /= 10;
+    }
+    len + signed as usize
+}
+
+/// Derives `StableAbi` for the item with the `abi_stable` feature, unless it is 128 bits, which has no stable ABI.
+///
+/// The encoding is recorded as a tag, because the check does not compare module paths.
+macro_rules! nonany_abi_stable {
+    (i128, $encoding:ident, $item:item) => {
+        $item
+    };
+    (i128, $item:item) => {
+        $item
+    };
+    (u128, $encoding:ident, $item:item) => {
+        $item
+    };
+    (u128, $item:item) => {
+        $item
+    };
+    ($int:ident, $encoding:ident, $item:item) => {
+        #[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+        #[cfg_attr(feature = "abi_stable", sabi(tag = abi_stable::type_layout::Tag::str(stringify!($encoding))))]
+        $item
+    };
+    ($int:ident, $item:item) => {
+        #[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+        $item
+    };
+}
+
 macro_rules! nonany {
-    ($name:ident, $nonzero:ident, $int:ty, $signed:ident) => {
-        /// An integer that is known not to equal `NICHE`.
-        /// 
-        /// This enables some memory layout optimization.
-        #[doc = concat!("For example, `Option<", stringify!($name), "<0>>` is the same size as `", stringify!($int), "`:")]
-        ///
-        /// ```rust
-        #[doc = concat!("assert_eq!(core::mem::size_of::<Option<nonany::", stringify!($name), "<0>>>(), core::mem::size_of::<", stringify!($int), ">());")]
-        /// ```
-        #[derive(Clone, Copy, Eq, Hash, PartialEq)]
-        #[repr(transparent)]
-        pub struct $name<const NICHE: $int>(core::num::$nonzero);
+    ($name:ident, $nonzero:ident, $int:ident, $signed:ident, $encoding:ident, $path:literal) => {
+        nonany_abi_stable!($int, $encoding,
+            /// An integer that is known not to equal `NICHE`.
+            /// 
+            /// This enables some memory layout optimization.
+            #[doc = concat!("For example, `Option<", stringify!($name), "<0>>` is the same size as `", stringify!($int), "`:")]
+            ///
+            /// ```rust
+            #[doc = concat!("assert_eq!(core::mem::size_of::<Option<", $path, stringify!($name), "<0>>>(), core::mem::size_of::<", stringify!($int), ">());")]
+            /// ```
+            #[derive(Clone, Copy, Eq, PartialEq)]
+            #[cfg_attr(feature = "zerocopy", derive(zerocopy::TryFromBytes, zerocopy::IntoBytes, zerocopy::KnownLayout, zerocopy::Immutable))]
+            #[repr(transparent)]
+            pub struct $name<const NICHE: $int>(core::num::$nonzero);
+        );
 
         impl<const NICHE: $int> $name<NICHE> {
             /// The niche value of this integer type.
@@ -29,9 +373,15 @@ macro_rules! nonany {
             /// The size of this integer type in bits.
             pub const BITS: u32 = <$int>::BITS;
 
+            /// The maximum length of the decimal representation of this integer type, including the sign.
+            pub const MAX_DECIMAL_LEN: usize = decimal_len(<$int>::MAX as u128, <$int>::MIN != 0);
+
+            /// The maximum length of the hexadecimal representation of this integer type, excluding the `0x` prefix.
+            pub const MAX_HEX_LEN: usize = <$int>::BITS as usize / 4;
+
             /// Creates a non-any if the given value is not `NICHE`.
             pub const fn new(value: $int) -> Option<Self> {
-                match core::num::$nonzero::new(value ^ NICHE) {
+                match core::num::$nonzero::new(nonany!(@encode, $encoding, value, NICHE)) {
                     Some(value) => Some(Self(value)),
                     None => None
                 }
@@ -44,17 +394,241 @@ macro_rules! nonany {
             ///
             /// The value must not be `NICHE`.
             pub const unsafe fn new_unchecked(value: $int) -> Self {
-                Self(core::num::$nonzero::new_unchecked(value ^ NICHE))
+                Self(core::num::$nonzero::new_unchecked(nonany!(@encode, $encoding, value, NICHE)))
             }
-    
+
+            /// Creates a non-any from a constant, which fails to compile if the constant is `NICHE`.
+            ///
+            /// ```rust
+            #[doc = concat!("const VALUE: ", $path, stringify!($name), "<5> = ", $path, stringify!($name), "::<5>::new_const::<7>();")]
+            /// assert_eq!(VALUE.get(), 7);
+            /// ```
+            ///
+            /// ```compile_fail
+            #[doc = concat!("const VALUE: ", $path, stringify!($name), "<5> = ", $path, stringify!($name), "::<5>::new_const::<5>();")]
+            /// ```
+            pub const fn new_const<const VALUE: $int>() -> Self {
+                struct Check<const N: $int, const V: $int>;
+
+                impl<const N: $int, const V: $int> Check<N, V> {
+                    #[allow(unconditional_panic, clippy::out_of_bounds_indexing)]
+                    const NONZERO: core::num::$nonzero = match core::num::$nonzero::new(nonany!(@encode, $encoding, V, N)) {
+                        Some(value) => value,
+                        None => {
+                            const VALUE_IS_NICHE: [core::num::$nonzero; 0] = [];
+                            VALUE_IS_NICHE[0]
+                        }
+                    };
+                }
+
+                Self(Check::<NICHE, VALUE>::NONZERO)
+            }
+
+            /// Converts a value like `try_from`, but returns the value and the niche along with the error.
+            pub fn try_from_with<T: Copy>(value: T) -> Result<Self, CheckedErrorWith<T, $int>>
+            where
+                Self: core::convert::TryFrom<T, Error = CheckedError>,
+            {
+                match core::convert::TryFrom::try_from(value) {
+                    Ok(value) => Ok(value),
+                    Err(error) => Err(CheckedErrorWith::new(error, value, NICHE))
+                }
+            }
+
             /// Returns the contained value as a primitive type.
             pub const fn get(self) -> $int {
-                self.0.get() ^ NICHE
+                nonany!(@decode, $encoding, self.0.get(), NICHE)
+            }
+
+            /// Returns `true` if the value equals the value of `other`, which can be a primitive integer, or a non-any
+            /// of any niche, width and encoding.
+            ///
+            /// ```rust
+            #[doc = concat!("let value = ", $path, stringify!($name), "::<1>::new(0).unwrap();")]
+            /// assert!(value.value_eq(0u8));
+            /// assert!(value.value_eq(nonany::NonMaxI64::new(0).unwrap()));
+            /// assert!(!value.value_eq(-1i32));
+            /// ```
+            pub fn value_eq<T: crate::Value>(self, other: T) -> bool {
+                crate::Value::wide(self.get()) == other.wide()
+            }
+
+            /// Compares the value with the value of `other`, which can be a primitive integer, or a non-any of any
+            /// niche, width and encoding.
+            ///
+            /// ```rust
+            /// use core::cmp::Ordering;
+            ///
+            #[doc = concat!("let value = ", $path, stringify!($name), "::<1>::new(0).unwrap();")]
+            /// assert_eq!(value.value_cmp(-1i8), Ordering::Greater);
+            /// assert_eq!(value.value_cmp(nonany::NonZeroU128::new(u128::MAX).unwrap()), Ordering::Less);
+            /// ```
+            pub fn value_cmp<T: crate::Value>(self, other: T) -> core::cmp::Ordering {
+                crate::Value::wide(self.get()).cmp(&other.wide())
+            }
+
+            /// Parses a non-any from a string slice with digits in the given base.
+            ///
+            /// The string is expected to be an optional `+` or `-` sign followed by digits, like the
+            /// `from_str_radix` function of the primitive integer types. For bases 16, 8 and 2 the digits
+            /// may be prefixed with `0x`, `0o` and `0b` respectively, as printed by the alternate `{:#x}`,
+            /// `{:#o}` and `{:#b}` formats. A radix outside of the range from 2 to 36 results in an
+            /// [`InvalidDigit`](ParseNonAnyErrorKind::InvalidDigit) error.
+            pub const fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseNonAnyError> {
+                Self::from_ascii_radix(src.as_bytes(), radix)
+            }
+
+            /// Parses a non-any from an ASCII byte slice with digits in the given base.
+            ///
+            /// See [`from_str_radix`](Self::from_str_radix) for the accepted format.
+            pub const fn from_ascii_radix(src: &[u8], radix: u32) -> Result<Self, ParseNonAnyError> {
+                if src.is_empty() {
+                    return Err(ParseNonAnyError { kind: ParseNonAnyErrorKind::Empty });
+                }
+
+                let mut index = 0;
+                let mut negative = false;
+                match src[0] {
+                    b'+' => index = 1,
+                    b'-' if <$int>::MIN != 0 => {
+                        negative = true;
+                        index = 1;
+                    },
+                    _ => ()
+                }
+
+                let prefix = match radix {
+                    16 => b'x',
+                    8 => b'o',
+                    2 => b'b',
+                    _ => 0
+                };
+                if prefix != 0 && src.len() > index + 1 && src[index] == b'0' && src[index + 1] == prefix {
+                    index += 2;
+                }
+
+                if index == src.len() || radix < 2 || radix > 36 {
+                    return Err(ParseNonAnyError { kind: ParseNonAnyErrorKind::InvalidDigit });
+                }
+
+                let overflow = if negative { ParseNonAnyErrorKind::NegOverflow } else { ParseNonAnyErrorKind::PosOverflow };
+                let mut value: $int = 0;
+                while index < src.len() {
+                    let digit = match src[index] {
+                        digit @ b'0'..=b'9' => (digit - b'0') as u32,
+                        digit @ b'a'..=b'z' => (digit - b'a') as u32 + 10,
+                        digit @ b'A'..=b'Z' => (digit - b'A') as u32 + 10,
+                        _ => u32::MAX
+                    };
+                    if digit >= radix {
+                        return Err(ParseNonAnyError { kind: ParseNonAnyErrorKind::InvalidDigit });
+                    }
+
+                    value = match value.checked_mul(radix as $int) {
+                        Some(value) => value,
+                        None => return Err(ParseNonAnyError { kind: overflow })
+                    };
+                    value = match if negative { value.checked_sub(digit as $int) } else { value.checked_add(digit as $int) } {
+                        Some(value) => value,
+                        None => return Err(ParseNonAnyError { kind: overflow })
+                    };
+
+                    index += 1;
+                }
+
+                match Self::new(value) {
+                    Some(value) => Ok(value),
+                    None => Err(ParseNonAnyError { kind: ParseNonAnyErrorKind::Niche })
+                }
+            }
+
+            /// Writes the value in decimal to the end of the buffer without using `core::fmt`.
+            ///
+            /// Returns the written part of the buffer, which is the same text as the `Display` implementation writes. Like
+            /// the other methods of the non-any, this takes `self` by value rather than by reference.
+            ///
+            /// ```rust
+            #[doc = concat!("let mut buffer = [0; ", $path, stringify!($name), "::<0>::MAX_DECIMAL_LEN];")]
+            #[doc = concat!("assert_eq!(", $path, stringify!($name), "::<0>::new(123).unwrap().write_decimal(&mut buffer), \"123\");")]
+            /// ```
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            pub fn write_decimal(self, buffer: &mut [u8; decimal_len(<$int>::MAX as u128, <$int>::MIN != 0)]) -> &str {
+                let mut value = self.get();
+                let negative = value < 0;
+                let mut index = buffer.len();
+                loop {
+                    index -= 1;
+                    buffer[index] = b'0' + ((value % 10) as i8).unsigned_abs();
+                    value /= 10;
+                    if value == 0 {
+                        break;
+                    }
+                }
+
+                if negative {
+                    index -= 1;
+                    buffer[index] = b'-';
+                }
+
+                // SAFETY: Only ASCII characters have been written to this part of the buffer.
+                unsafe { core::str::from_utf8_unchecked(&buffer[index..]) }
+            }
+
+            /// Writes the value in lowercase hexadecimal to the end of the buffer without using `core::fmt`.
+            ///
+            /// Returns the written part of the buffer, which is the same text as the `LowerHex` implementation writes.
+            pub fn write_lower_hex(self, buffer: &mut [u8; <$int>::BITS as usize / 4]) -> &str {
+                self.write_hex(buffer, b"0123456789abcdef")
+            }
+
+            /// Writes the value in uppercase hexadecimal to the end of the buffer without using `core::fmt`.
+            ///
+            /// Returns the written part of the buffer, which is the same text as the `UpperHex` implementation writes.
+            pub fn write_upper_hex(self, buffer: &mut [u8; <$int>::BITS as usize / 4]) -> &str {
+                self.write_hex(buffer, b"0123456789ABCDEF")
+            }
+
+            fn write_hex<'a>(self, buffer: &'a mut [u8; <$int>::BITS as usize / 4], digits: &[u8; 16]) -> &'a str {
+                // Negative values are written in two's complement, like the `core::fmt` implementations.
+                let value = self.get();
+                let mut index = buffer.len();
+                let mut shift = 0;
+                loop {
+                    index -= 1;
+                    buffer[index] = digits[((value >> shift) & 0xf) as usize];
+                    shift += 4;
+                    if shift == <$int>::BITS || value >> shift == 0 {
+                        break;
+                    }
+                }
+
+                // SAFETY: Only ASCII characters have been written to this part of the buffer.
+                unsafe { core::str::from_utf8_unchecked(&buffer[index..]) }
             }
 
             nonany!(@$signed, $name, $nonzero, $int);
         }
 
+        impl<const NICHE: $int> sealed::Sealed for $name<NICHE> {}
+
+        impl<const NICHE: $int> NonAny for $name<NICHE> {
+            type Int = $int;
+
+            const NICHE: $int = NICHE;
+
+            fn new(value: $int) -> Option<Self> {
+                Self::new(value)
+            }
+
+            unsafe fn new_unchecked(value: $int) -> Self {
+                Self::new_unchecked(value)
+            }
+
+            fn get(self) -> $int {
+                self.get()
+            }
+        }
+
         impl<const NICHE: $int> core::cmp::PartialOrd for $name<NICHE> {
             fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
                 Some(self.cmp(other))
@@ -67,6 +641,34 @@ macro_rules! nonany {
             }
         }
 
+        /// Hashes the value like the primitive integer, so that the hash does not depend on the niche or the
+        /// encoding. This is guaranteed to stay the same.
+        impl<const NICHE: $int> core::hash::Hash for $name<NICHE> {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.get().hash(state)
+            }
+        }
+
+        impl<const NICHE: $int> core::str::FromStr for $name<NICHE> {
+            type Err = ParseNonAnyError;
+
+            /// Parses a decimal non-any, or a hexadecimal, octal or binary non-any when the digits
+            /// are prefixed with `0x`, `0o` or `0b` respectively.
+            fn from_str(src: &str) -> Result<Self, Self::Err> {
+                let digits = match src.as_bytes() {
+                    [b'+' | b'-', digits @ ..] => digits,
+                    digits => digits
+                };
+                let radix = match digits {
+                    [b'0', b'x', ..] => 16,
+                    [b'0', b'o', ..] => 8,
+                    [b'0', b'b', ..] => 2,
+                    _ => 10
+                };
+                Self::from_str_radix(src, radix)
+            }
+        }
+
         impl<const NICHE: $int> core::fmt::Debug for $name<NICHE> {
             fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                 core::fmt::Debug::fmt(&self.get(), f)
@@ -145,21 +747,37 @@ macro_rules! nonany {
     (@unsigned, $name:ident, $nonzero:ident, $int:ty) => {
         
     };
+
+    (@encode, xor, $value:expr, $niche:expr) => {
+        $value ^ $niche
+    };
+
+    (@decode, xor, $bits:expr, $niche:expr) => {
+        $bits ^ $niche
+    };
+
+    (@encode, offset, $value:expr, $niche:expr) => {
+        $value.wrapping_sub($niche)
+    };
+
+    (@decode, offset, $bits:expr, $niche:expr) => {
+        $bits.wrapping_add($niche)
+    };
 }
 
-nonany!(NonAnyI8, NonZeroI8, i8, signed);
-nonany!(NonAnyI16, NonZeroI16, i16, signed);
-nonany!(NonAnyI32, NonZeroI32, i32, signed);
-nonany!(NonAnyI64, NonZeroI64, i64, signed);
-nonany!(NonAnyI128, NonZeroI128, i128, signed);
-nonany!(NonAnyIsize, NonZeroIsize, isize, signed);
+nonany!(NonAnyI8, NonZeroI8, i8, signed, xor, "nonany::");
+nonany!(NonAnyI16, NonZeroI16, i16, signed, xor, "nonany::");
+nonany!(NonAnyI32, NonZeroI32, i32, signed, xor, "nonany::");
+nonany!(NonAnyI64, NonZeroI64, i64, signed, xor, "nonany::");
+nonany!(NonAnyI128, NonZeroI128, i128, signed, xor, "nonany::");
+nonany!(NonAnyIsize, NonZeroIsize, isize, signed, xor, "nonany::");
 
-nonany!(NonAnyU8, NonZeroU8, u8, unsigned);
-nonany!(NonAnyU16, NonZeroU16, u16, unsigned);
-nonany!(NonAnyU32, NonZeroU32, u32, unsigned);
-nonany!(NonAnyU64, NonZeroU64, u64, unsigned);
-nonany!(NonAnyU128, NonZeroU128, u128, unsigned);
-nonany!(NonAnyUsize, NonZeroUsize, usize, unsigned);
+nonany!(NonAnyU8, NonZeroU8, u8, unsigned, xor, "nonany::");
+nonany!(NonAnyU16, NonZeroU16, u16, unsigned, xor, "nonany::");
+nonany!(NonAnyU32, NonZeroU32, u32, unsigned, xor, "nonany::");
+nonany!(NonAnyU64, NonZeroU64, u64, unsigned, xor, "nonany::");
+nonany!(NonAnyU128, NonZeroU128, u128, unsigned, xor, "nonany::");
+nonany!(NonAnyUsize, NonZeroUsize, usize, unsigned, xor, "nonany::");
 
 macro_rules! nonany_tryfrom_int {
     ($nonany:ident, $to_int:ty) => {
@@ -178,14 +796,16 @@ macro_rules! nonany_tryfrom_int {
     };
 
     ($nonany:ident, $to_int:ty, $from_int:ty) => {
-        impl<const NICHE: $to_int> core::convert::TryFrom<$from_int> for $crate::$nonany<NICHE> {
+        impl<const NICHE: $to_int> core::convert::TryFrom<$from_int> for $nonany<NICHE> {
             type Error = $crate::CheckedError;    
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
             fn try_from(value: $from_int) -> Result<Self, Self::Error> {
                 match core::convert::TryInto::<$to_int>::try_into(value) {
                     Ok(value) => match Self::new(value) {
                         Some(value) => Ok(value),
                         None => Err(CheckedError::Niche)
                     },
+                    Err(_) if value < 0 => Err(CheckedError::Underflow),
                     Err(_) => Err(CheckedError::Overflow)
                 }
             }
@@ -239,12 +859,14 @@ macro_rules! nonany_tryfrom_nonany {
     ($to_nonany:ident, $to_int:ty, $from_nonany:ident, $from_int:ty) => {
         impl<const TO_NICHE: $to_int, const FROM_NICHE: $from_int> core::convert::TryFrom<$crate::$from_nonany<FROM_NICHE>> for $crate::$to_nonany<TO_NICHE> {
             type Error = $crate::CheckedError;    
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
             fn try_from(value: $crate::$from_nonany<FROM_NICHE>) -> Result<Self, Self::Error> {
                 match core::convert::TryInto::<$to_int>::try_into(value.get()) {
                     Ok(value) => match Self::new(value) {
                         Some(value) => Ok(value),
                         None => Err(CheckedError::Niche)
                     },
+                    Err(_) if value.get() < 0 => Err(CheckedError::Underflow),
                     Err(_) => Err(CheckedError::Overflow)
                 }
             }
@@ -285,12 +907,14 @@ macro_rules! nonany_tryfrom_nonzero {
     ($to_nonany:ident, $to_int:ty, $from_nonzero:ident, $from_int:ty) => {
         impl<const NICHE: $to_int> core::convert::TryFrom<core::num::$from_nonzero> for $crate::$to_nonany<NICHE> {
             type Error = $crate::CheckedError;    
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
             fn try_from(value: core::num::$from_nonzero) -> Result<Self, Self::Error> {
                 match core::convert::TryInto::<$to_int>::try_into(value.get()) {
                     Ok(value) => match Self::new(value) {
                         Some(value) => Ok(value),
                         None => Err(CheckedError::Niche)
                     },
+                    Err(_) if value.get() < 0 => Err(CheckedError::Underflow),
                     Err(_) => Err(CheckedError::Overflow)
                 }
             }
@@ -339,5 +963,245 @@ nonany_typedef!(NonZeroU64, NonMinU64, NonMaxU64, NonAnyU64, u64);
 nonany_typedef!(NonZeroU128, NonMinU128, NonMaxU128, NonAnyU128, u128);
 nonany_typedef!(NonZeroUsize, NonMinUsize, NonMaxUsize, NonAnyUsize, usize);
 
+macro_rules! nonany_endian {
+    ($name:ident, $nonany:ident, $nonzero:ident, $int:ident, $to:ident, $from:ident, $endian:literal) => {
+        nonany_abi_stable!($int,
+            #[doc = concat!("An integer that is known not to equal zero, stored as ", $endian, " bytes without any alignment requirement.")]
+            ///
+            /// This is meant for fields of packed structures and wire formats. The bytes hold the value itself, so they
+            /// match the bytes of a plain integer field, and a zero on the wire is rejected or read as `None`. There are
+            /// no versions with other niches, because those are stored XOR encoded and would not match the wire bytes.
+            #[doc = concat!("`Option<", stringify!($name), ">` is the same size as `", stringify!($int), "`, and has an alignment of 1:")]
+            ///
+            /// ```rust
+            #[doc = concat!("assert_eq!(core::mem::size_of::<Option<nonany::", stringify!($name), ">>(), core::mem::size_of::<", stringify!($int), ">());")]
+            #[doc = concat!("assert_eq!(core::mem::align_of::<Option<nonany::", stringify!($name), ">>(), 1);")]
+            /// ```
+            ///
+            #[doc = concat!("Conversions to other widths, parsing with a radix and writing into a buffer go through [`", stringify!($nonany), "`], which [`to_nonany`](Self::to_nonany) returns.")]
+            #[derive(Clone, Copy, Eq, PartialEq)]
+            #[cfg_attr(feature = "zerocopy", derive(zerocopy::TryFromBytes, zerocopy::IntoBytes, zerocopy::KnownLayout, zerocopy::Immutable, zerocopy::Unaligned))]
+            #[repr(C, packed)]
+            pub struct $name(core::num::$nonzero);
+        );
+
+        impl $name {
+            /// The size of this integer type in bits.
+            pub const BITS: u32 = <$int>::BITS;
+
+            /// Creates a non-zero if the given value is not zero.
+            pub const fn new(value: $int) -> Option<Self> {
+                match core::num::$nonzero::new(value) {
+                    Some(value) => Some(Self::from_nonzero(value)),
+                    None => None
+                }
+            }
+
+            /// Creates a non-zero without checking whether the value is zero.
+            /// This results in undefined behaviour if the value is zero.
+            ///
+            /// # Safety
+            ///
+            /// The value must not be zero.
+            pub const unsafe fn new_unchecked(value: $int) -> Self {
+                Self::from_nonzero(core::num::$nonzero::new_unchecked(value))
+            }
+
+            #[doc = concat!("Converts a [`", stringify!($nonzero), "`](core::num::", stringify!($nonzero), ") to ", $endian, " bytes.")]
+            pub const fn from_nonzero(value: core::num::$nonzero) -> Self {
+                // SAFETY: Changing the byte order of a nonzero value keeps it nonzero.
+                Self(unsafe { core::num::$nonzero::new_unchecked(value.get().$to()) })
+            }
+
+            #[doc = concat!("Converts the value back to a naturally aligned [`", stringify!($nonzero), "`](core::num::", stringify!($nonzero), ").")]
+            pub const fn to_nonzero(self) -> core::num::$nonzero {
+                let bits = self.0;
+                // SAFETY: Changing the byte order of a nonzero value keeps it nonzero.
+                unsafe { core::num::$nonzero::new_unchecked(<$int>::$from(bits.get())) }
+            }
+
+            #[doc = concat!("Converts a [`", stringify!($nonany), "<0>`](", stringify!($nonany), ") to ", $endian, " bytes.")]
+            pub const fn from_nonany(value: $nonany<0>) -> Self {
+                Self::from_nonzero(value.0)
+            }
+
+            #[doc = concat!("Converts the value back to a naturally aligned [`", stringify!($nonany), "<0>`](", stringify!($nonany), ").")]
+            pub const fn to_nonany(self) -> $nonany<0> {
+                $nonany(self.to_nonzero())
+            }
+
+            /// Returns the contained value as a primitive type.
+            pub const fn get(self) -> $int {
+                self.to_nonzero().get()
+            }
+
+            #[doc = concat!("Creates a non-zero from its ", $endian, " bytes, which hold the value itself.")]
+            ///
+            /// Returns `None` if all bytes are zero.
+            pub const fn from_bytes(bytes: [u8; core::mem::size_of::<$int>()]) -> Option<Self> {
+                match core::num::$nonzero::new(<$int>::from_ne_bytes(bytes)) {
+                    Some(bits) => Some(Self(bits)),
+                    None => None
+                }
+            }
+
+            #[doc = concat!("Returns the ", $endian, " bytes of the non-zero, which hold the value itself.")]
+            pub const fn to_bytes(self) -> [u8; core::mem::size_of::<$int>()] {
+                let bits = self.0;
+                bits.get().to_ne_bytes()
+            }
+
+            /// Returns `true` if the value equals the value of `other`, which can be a primitive integer, or a non-any
+            /// of any niche, width and encoding.
+            pub fn value_eq<T: Value>(self, other: T) -> bool {
+                self.to_nonany().value_eq(other)
+            }
+
+            /// Compares the value with the value of `other`, which can be a primitive integer, or a non-any of any
+            /// niche, width and encoding.
+            pub fn value_cmp<T: Value>(self, other: T) -> core::cmp::Ordering {
+                self.to_nonany().value_cmp(other)
+            }
+        }
+
+        impl sealed::Sealed for $name {}
+
+        impl NonAny for $name {
+            type Int = $int;
+
+            const NICHE: $int = 0;
+
+            fn new(value: $int) -> Option<Self> {
+                Self::new(value)
+            }
+
+            unsafe fn new_unchecked(value: $int) -> Self {
+                Self::new_unchecked(value)
+            }
+
+            fn get(self) -> $int {
+                self.get()
+            }
+        }
+
+        impl From<$nonany<0>> for $name {
+            fn from(value: $nonany<0>) -> Self {
+                Self::from_nonany(value)
+            }
+        }
+
+        impl From<$name> for $nonany<0> {
+            fn from(value: $name) -> Self {
+                value.to_nonany()
+            }
+        }
+
+        impl From<core::num::$nonzero> for $name {
+            fn from(value: core::num::$nonzero) -> Self {
+                Self::from_nonzero(value)
+            }
+        }
+
+        impl From<$name> for core::num::$nonzero {
+            fn from(value: $name) -> Self {
+                value.to_nonzero()
+            }
+        }
+
+        impl core::convert::TryFrom<$int> for $name {
+            type Error = CheckedError;
+
+            fn try_from(value: $int) -> Result<Self, Self::Error> {
+                match Self::new(value) {
+                    Some(value) => Ok(value),
+                    None => Err(CheckedError::Niche)
+                }
+            }
+        }
+
+        impl core::str::FromStr for $name {
+            type Err = ParseNonAnyError;
+
+            fn from_str(src: &str) -> Result<Self, Self::Err> {
+                src.parse().map(Self::from_nonany)
+            }
+        }
+
+        impl core::cmp::PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl core::cmp::Ord for $name {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.get().cmp(&other.get())
+            }
+        }
+
+        /// Hashes the value like the primitive integer, so that the hash does not depend on the byte order. This is
+        /// guaranteed to stay the same.
+        impl core::hash::Hash for $name {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.get().hash(state)
+            }
+        }
+
+        nonany_endian!(@fmt, $name, Debug, Display, Binary, Octal, LowerHex, UpperHex);
+    };
+
+    (@fmt, $name:ident, $($trait:ident),*) => {
+        $(
+            impl core::fmt::$trait for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    core::fmt::$trait::fmt(&self.get(), f)
+                }
+            }
+        )*
+    };
+}
+
+nonany_endian!(NonZeroI16Be, NonAnyI16, NonZeroI16, i16, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroI32Be, NonAnyI32, NonZeroI32, i32, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroI64Be, NonAnyI64, NonZeroI64, i64, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroI128Be, NonAnyI128, NonZeroI128, i128, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroU16Be, NonAnyU16, NonZeroU16, u16, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroU32Be, NonAnyU32, NonZeroU32, u32, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroU64Be, NonAnyU64, NonZeroU64, u64, to_be, from_be, "big-endian");
+nonany_endian!(NonZeroU128Be, NonAnyU128, NonZeroU128, u128, to_be, from_be, "big-endian");
+
+nonany_endian!(NonZeroI16Le, NonAnyI16, NonZeroI16, i16, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroI32Le, NonAnyI32, NonZeroI32, i32, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroI64Le, NonAnyI64, NonZeroI64, i64, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroI128Le, NonAnyI128, NonZeroI128, i128, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroU16Le, NonAnyU16, NonZeroU16, u16, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroU32Le, NonAnyU32, NonZeroU32, u32, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroU64Le, NonAnyU64, NonZeroU64, u64, to_le, from_le, "little-endian");
+nonany_endian!(NonZeroU128Le, NonAnyU128, NonZeroU128, u128, to_le, from_le, "little-endian");
+
+pub mod offset;
+
+pub mod fixed;
+
+pub mod ffi;
+
+#[cfg(feature = "alloc")]
+pub mod index;
+
+#[cfg(feature = "alloc")]
+pub mod arena;
+
+#[cfg(feature = "alloc")]
+pub mod map;
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+pub mod atomic;
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+pub mod id;
+
+#[cfg(feature = "nightly")]
+pub mod nightly;
+
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -0,0 +1,679 @@
+//! A hash map and a hash set with `NonAny*` keys, which use the niche to mark empty slots.
+//!
+//! An `Option` of a `NonAny*` type stores `None` as zero, so a table of `Option<K>` keys needs no control bytes to tell
+//! empty slots apart from occupied ones. [`NicheMap`] and [`NicheSet`] use open addressing with linear probing over
+//! such a table, and remove keys with backward-shift deletion, so there are no tombstones either. The values of a map
+//! are stored in a separate table, so probing only touches the keys.
+//!
+//! ```rust
+//! use nonany::NonZeroU32;
+//! use nonany::map::NicheMap;
+//!
+//! let mut symbols = NicheMap::new();
+//! let main = NonZeroU32::new(1).unwrap();
+//! let exit = NonZeroU32::new(2).unwrap();
+//!
+//! assert_eq!(symbols.insert(main, "main"), None);
+//! assert_eq!(symbols.insert(exit, "exit"), None);
+//! assert_eq!(symbols.insert(main, "start"), Some("main"));
+//!
+//! assert_eq!(symbols.get(main), Some(&"start"));
+//! assert_eq!(symbols.remove(exit), Some("exit"));
+//! assert_eq!(symbols.len(), 1);
+//! ```
+//!
+//! Keys are hashed with a fast multiplicative hash of their value, which is meant for integer keys on hot paths. It
+//! does not protect against keys that are chosen to collide, so prefer a `HashMap` for keys that come from untrusted
+//! input.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hasher;
+use core::iter::{FromIterator, FusedIterator};
+use core::mem::MaybeUninit;
+use core::ops::{Index, IndexMut};
+use crate::NonAny;
+
+const SEED: u64 = 0x517c_c1b7_2722_0a95;
+const MIN_BUCKETS: usize = 8;
+
+#[derive(Default)]
+struct KeyHasher(u64);
+
+impl KeyHasher {
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for KeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.add(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.add(value as u64);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.add(value as u64);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.add(value as u64);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.add(value);
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.add(value as u64);
+        self.add((value >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.add(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Returns the number of buckets needed to hold `len` keys, which is zero or a power of two.
+fn buckets_for(len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let buckets = len.checked_mul(4).map(|buckets| (buckets + 2) / 3).and_then(usize::checked_next_power_of_two);
+    buckets.expect("capacity overflow").max(MIN_BUCKETS)
+}
+
+/// A hash map with `NonAny*` keys, which uses the niche to mark empty slots.
+pub struct NicheMap<K: NonAny, V> {
+    keys: Vec<Option<K>>,
+    values: Vec<MaybeUninit<V>>,
+    len: usize,
+}
+
+impl<K: NonAny, V> NicheMap<K, V> {
+    /// Creates an empty map, which does not allocate until a key is inserted.
+    pub fn new() -> Self {
+        Self { keys: Vec::new(), values: Vec::new(), len: 0 }
+    }
+
+    /// Creates an empty map with space for at least `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut map = Self::new();
+        map.resize(buckets_for(capacity));
+        map
+    }
+
+    /// Returns the number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of keys the map can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.keys.len() / 4 * 3
+    }
+
+    /// Reserves space for at least `additional` more keys.
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self.len.checked_add(additional).expect("capacity overflow");
+        if len > self.capacity() {
+            self.resize(buckets_for(len));
+        }
+    }
+
+    /// Shrinks the capacity of the map as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        let buckets = buckets_for(self.len);
+        if buckets < self.keys.len() {
+            self.resize(buckets);
+        }
+    }
+
+    /// Inserts a key and a value, and returns the previous value of the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.find_or_reserve(key) {
+            Ok(index) => {
+                // SAFETY: The slot of an occupied key is initialized.
+                Some(core::mem::replace(unsafe { self.values[index].assume_init_mut() }, value))
+            }
+            Err(index) => {
+                self.occupy(index, key, value);
+                None
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value of the key, and inserts the value returned by the function first if
+    /// the key is not in the map.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> &mut V {
+        let index = match self.find_or_reserve(key) {
+            Ok(index) => index,
+            Err(index) => {
+                self.occupy(index, key, f());
+                index
+            }
+        };
+        // SAFETY: The slot of an occupied key is initialized.
+        unsafe { self.values[index].assume_init_mut() }
+    }
+
+    /// Returns `true` if the map contains the key.
+    pub fn contains_key(&self, key: K) -> bool {
+        self.find(key).is_ok()
+    }
+
+    /// Returns a reference to the value of the key.
+    pub fn get(&self, key: K) -> Option<&V> {
+        match self.find(key) {
+            // SAFETY: The slot of an occupied key is initialized.
+            Ok(index) => Some(unsafe { self.values[index].assume_init_ref() }),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value of the key.
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        match self.find(key) {
+            // SAFETY: The slot of an occupied key is initialized.
+            Ok(index) => Some(unsafe { self.values[index].assume_init_mut() }),
+            Err(_) => None,
+        }
+    }
+
+    /// Removes the key and returns its value.
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        match self.find(key) {
+            Ok(index) => Some(self.remove_at(index)),
+            Err(_) => None,
+        }
+    }
+
+    /// Removes all keys for which the function returns `false`.
+    pub fn retain(&mut self, mut f: impl FnMut(K, &mut V) -> bool) {
+        // Removing a key only moves keys that come after it in the same cluster, so visiting the buckets in order
+        // starting after an empty bucket visits every key exactly once.
+        let buckets = self.keys.len();
+        let start = match self.keys.iter().position(Option::is_none) {
+            Some(start) => start,
+            None => return,
+        };
+
+        let mut offset = 1;
+        while offset < buckets {
+            let index = (start + offset) & (buckets - 1);
+            match self.keys[index] {
+                // SAFETY: The slot of an occupied key is initialized.
+                Some(key) if !f(key, unsafe { self.values[index].assume_init_mut() }) => drop(self.remove_at(index)),
+                _ => offset += 1,
+            }
+        }
+    }
+
+    /// Removes all keys, and keeps the allocated memory.
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false)
+    }
+
+    /// Returns an iterator over the keys and the values, in an unspecified order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { keys: self.keys.iter().copied(), values: self.values.iter(), len: self.len }
+    }
+
+    /// Returns an iterator over the keys and mutable references to the values, in an unspecified order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { keys: self.keys.iter().copied(), values: self.values.iter_mut(), len: self.len }
+    }
+
+    /// Returns an iterator over the keys, in an unspecified order.
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values, in an unspecified order.
+    pub fn values(&self) -> impl ExactSizeIterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over mutable references to the values, in an unspecified order.
+    pub fn values_mut(&mut self) -> impl ExactSizeIterator<Item = &mut V> + '_ {
+        self.iter_mut().map(|(_, value)| value)
+    }
+
+    fn home(&self, key: K) -> usize {
+        let mut hasher = KeyHasher::default();
+        key.hash(&mut hasher);
+        // The high bits of a multiplicative hash are the best mixed ones.
+        (hasher.finish() >> (64 - self.keys.len().trailing_zeros())) as usize
+    }
+
+    /// Returns the index of the key, or the index of the empty bucket where it would be inserted.
+    fn find(&self, key: K) -> Result<usize, usize> {
+        if self.keys.is_empty() {
+            return Err(0);
+        }
+
+        // There is always an empty bucket, because the table is never full.
+        let mask = self.keys.len() - 1;
+        let mut index = self.home(key);
+        loop {
+            match self.keys[index] {
+                Some(other) if other == key => return Ok(index),
+                Some(_) => index = (index + 1) & mask,
+                None => return Err(index),
+            }
+        }
+    }
+
+    fn find_or_reserve(&mut self, key: K) -> Result<usize, usize> {
+        match self.find(key) {
+            Err(_) if self.len == self.capacity() => {
+                self.reserve(1);
+                self.find(key)
+            }
+            result => result,
+        }
+    }
+
+    fn occupy(&mut self, index: usize, key: K, value: V) {
+        self.values[index] = MaybeUninit::new(value);
+        self.keys[index] = Some(key);
+        self.len += 1;
+    }
+
+    fn remove_at(&mut self, mut hole: usize) -> V {
+        self.keys[hole] = None;
+        self.len -= 1;
+        // SAFETY: The slot was occupied, and is not read again now that its key is removed.
+        let value = unsafe { self.values[hole].as_ptr().read() };
+
+        // Move the following keys of the cluster back into the hole, unless that would move them before their home.
+        let mask = self.keys.len() - 1;
+        let mut index = hole;
+        loop {
+            index = (index + 1) & mask;
+            let key = match self.keys[index] {
+                Some(key) => key,
+                None => break,
+            };
+            if index.wrapping_sub(self.home(key)) & mask >= index.wrapping_sub(hole) & mask {
+                self.keys[hole] = Some(key);
+                self.keys[index] = None;
+                self.values.swap(hole, index);
+                hole = index;
+            }
+        }
+
+        value
+    }
+
+    fn resize(&mut self, buckets: usize) {
+        let keys = core::mem::replace(&mut self.keys, alloc::vec![None; buckets]);
+        let values = core::mem::replace(&mut self.values, Vec::with_capacity(buckets));
+        self.values.resize_with(buckets, MaybeUninit::uninit);
+
+        for (key, value) in keys.into_iter().zip(values.iter()) {
+            if let Some(key) = key {
+                let index = match self.find(key) {
+                    Ok(index) | Err(index) => index,
+                };
+                self.keys[index] = Some(key);
+                // SAFETY: The slot of an occupied key is initialized, and the old table does not drop it.
+                self.values[index] = MaybeUninit::new(unsafe { value.as_ptr().read() });
+            }
+        }
+    }
+}
+
+impl<K: NonAny, V> Drop for NicheMap<K, V> {
+    fn drop(&mut self) {
+        if core::mem::needs_drop::<V>() {
+            for (key, value) in self.keys.iter().zip(self.values.iter_mut()) {
+                if key.is_some() {
+                    // SAFETY: The slot of an occupied key is initialized.
+                    unsafe { value.as_mut_ptr().drop_in_place() }
+                }
+            }
+        }
+    }
+}
+
+impl<K: NonAny, V> Default for NicheMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: NonAny, V: Clone> Clone for NicheMap<K, V> {
+    fn clone(&self) -> Self {
+        let mut values = Vec::with_capacity(self.keys.len());
+        values.resize_with(self.keys.len(), MaybeUninit::uninit);
+        let mut map = Self { keys: alloc::vec![None; self.keys.len()], values, len: 0 };
+        // A bucket is only occupied after its value is cloned, so a panic does not drop uninitialized values.
+        for (index, (key, value)) in self.keys.iter().zip(self.values.iter()).enumerate() {
+            if let Some(key) = *key {
+                // SAFETY: The slot of an occupied key is initialized.
+                let value = unsafe { value.assume_init_ref() }.clone();
+                map.occupy(index, key, value);
+            }
+        }
+        map
+    }
+}
+
+impl<K: NonAny, V: PartialEq> PartialEq for NicheMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: NonAny, V: Eq> Eq for NicheMap<K, V> {}
+
+impl<K: NonAny, V: fmt::Debug> fmt::Debug for NicheMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: NonAny, V> Index<K> for NicheMap<K, V> {
+    type Output = V;
+
+    fn index(&self, key: K) -> &V {
+        self.get(key).expect("key is not in the map")
+    }
+}
+
+impl<K: NonAny, V> IndexMut<K> for NicheMap<K, V> {
+    fn index_mut(&mut self, key: K) -> &mut V {
+        self.get_mut(key).expect("key is not in the map")
+    }
+}
+
+impl<K: NonAny, V> FromIterator<(K, V)> for NicheMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: NonAny, V> Extend<(K, V)> for NicheMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+macro_rules! map_iter {
+    ($(#[$attr:meta])* $iter:ident, [$($lifetime:lifetime)?], $keys:ty, $values:ty, $item:ty, $value:ident => $result:expr) => {
+        $(#[$attr])*
+        pub struct $iter<$($lifetime,)? K: NonAny, V> {
+            keys: $keys,
+            values: $values,
+            len: usize,
+        }
+
+        impl<$($lifetime,)? K: NonAny, V> Iterator for $iter<$($lifetime,)? K, V> {
+            type Item = (K, $item);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                while let (Some(key), Some($value)) = (self.keys.next(), self.values.next()) {
+                    if let Some(key) = key {
+                        self.len -= 1;
+                        // SAFETY: The slot of an occupied key is initialized.
+                        return Some((key, unsafe { $result }));
+                    }
+                }
+                None
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($lifetime,)? K: NonAny, V> ExactSizeIterator for $iter<$($lifetime,)? K, V> {}
+
+        impl<$($lifetime,)? K: NonAny, V> FusedIterator for $iter<$($lifetime,)? K, V> {}
+    };
+}
+
+map_iter!(
+    /// An iterator over the keys and the values of a [`NicheMap`].
+    Iter, ['a], core::iter::Copied<core::slice::Iter<'a, Option<K>>>, core::slice::Iter<'a, MaybeUninit<V>>, &'a V,
+    value => value.assume_init_ref()
+);
+
+map_iter!(
+    /// An iterator over the keys and mutable references to the values of a [`NicheMap`].
+    IterMut, ['a], core::iter::Copied<core::slice::Iter<'a, Option<K>>>, core::slice::IterMut<'a, MaybeUninit<V>>, &'a mut V,
+    value => value.assume_init_mut()
+);
+
+map_iter!(
+    /// An iterator that moves the keys and the values out of a [`NicheMap`].
+    IntoIter, [], alloc::vec::IntoIter<Option<K>>, alloc::vec::IntoIter<MaybeUninit<V>>, V,
+    value => value.as_ptr().read()
+);
+
+impl<K: NonAny, V> Drop for IntoIter<K, V> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<K: NonAny, V> IntoIterator for NicheMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(mut self) -> IntoIter<K, V> {
+        let len = core::mem::replace(&mut self.len, 0);
+        let keys = core::mem::take(&mut self.keys);
+        let values = core::mem::take(&mut self.values);
+        IntoIter { keys: keys.into_iter(), values: values.into_iter(), len }
+    }
+}
+
+impl<'a, K: NonAny, V> IntoIterator for &'a NicheMap<K, V> {
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K: NonAny, V> IntoIterator for &'a mut NicheMap<K, V> {
+    type Item = (K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// A hash set of `NonAny*` keys, which uses the niche to mark empty slots.
+///
+/// ```rust
+/// use nonany::NonMaxU16;
+/// use nonany::map::NicheSet;
+///
+/// let mut set: NicheSet<NonMaxU16> = (1..=3).filter_map(NonMaxU16::new).collect();
+/// assert!(set.contains(NonMaxU16::new(2).unwrap()));
+/// assert!(set.remove(NonMaxU16::new(2).unwrap()));
+/// assert!(!set.insert(NonMaxU16::new(3).unwrap()));
+/// assert_eq!(set.len(), 2);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct NicheSet<K: NonAny> {
+    map: NicheMap<K, ()>,
+}
+
+impl<K: NonAny> NicheSet<K> {
+    /// Creates an empty set, which does not allocate until a key is inserted.
+    pub fn new() -> Self {
+        Self { map: NicheMap::new() }
+    }
+
+    /// Creates an empty set with space for at least `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { map: NicheMap::with_capacity(capacity) }
+    }
+
+    /// Returns the number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the number of keys the set can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Reserves space for at least `additional` more keys.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+
+    /// Shrinks the capacity of the set as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+
+    /// Inserts a key, and returns `true` if it was not in the set.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Returns `true` if the set contains the key.
+    pub fn contains(&self, key: K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Removes a key, and returns `true` if it was in the set.
+    pub fn remove(&mut self, key: K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    /// Removes all keys for which the function returns `false`.
+    pub fn retain(&mut self, mut f: impl FnMut(K) -> bool) {
+        self.map.retain(|key, _| f(key))
+    }
+
+    /// Removes all keys, and keeps the allocated memory.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Returns an iterator over the keys, in an unspecified order.
+    pub fn iter(&self) -> SetIter<'_, K> {
+        SetIter { iter: self.map.iter() }
+    }
+}
+
+impl<K: NonAny> Default for NicheSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: NonAny> fmt::Debug for NicheSet<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: NonAny> FromIterator<K> for NicheSet<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<K: NonAny> Extend<K> for NicheSet<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|key| (key, ())))
+    }
+}
+
+/// An iterator over the keys of a [`NicheSet`].
+pub struct SetIter<'a, K: NonAny> {
+    iter: Iter<'a, K, ()>,
+}
+
+impl<'a, K: NonAny> Iterator for SetIter<'a, K> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<K> {
+        self.iter.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K: NonAny> ExactSizeIterator for SetIter<'a, K> {}
+
+impl<'a, K: NonAny> FusedIterator for SetIter<'a, K> {}
+
+/// An iterator that moves the keys out of a [`NicheSet`].
+pub struct SetIntoIter<K: NonAny> {
+    iter: IntoIter<K, ()>,
+}
+
+impl<K: NonAny> Iterator for SetIntoIter<K> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<K> {
+        self.iter.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K: NonAny> ExactSizeIterator for SetIntoIter<K> {}
+
+impl<K: NonAny> FusedIterator for SetIntoIter<K> {}
+
+impl<K: NonAny> IntoIterator for NicheSet<K> {
+    type Item = K;
+    type IntoIter = SetIntoIter<K>;
+
+    fn into_iter(self) -> SetIntoIter<K> {
+        SetIntoIter { iter: self.map.into_iter() }
+    }
+}
+
+impl<'a, K: NonAny> IntoIterator for &'a NicheSet<K> {
+    type Item = K;
+    type IntoIter = SetIter<'a, K>;
+
+    fn into_iter(self) -> SetIter<'a, K> {
+        self.iter()
+    }
+}
@@ -0,0 +1,411 @@
+//! `NonMin*` and `NonMax*` types that store the value itself, using unstable pattern types.
+//!
+//! When the niche is at the edge of the integer range, it can be expressed as a range of valid values, which the
+//! compiler uses for layout optimization directly. The types in this module store the value as is, so no XOR is
+//! needed to load or store the value, and transmuting them to their underlying integer type results in the value
+//! itself. They have the same API as the `NonMin*` and `NonMax*` aliases in the crate root, and can be converted
+//! into each other with `From`. They also implement the `serde`, `bytemuck`, `equivalent` and varint support of the
+//! aliases, except for `TransparentWrapper`, as there is no inner type that they wrap. The `zerocopy` and
+//! `abi_stable` derives do not support pattern types, so those are not implemented.
+//!
+//! The valid range is declared with `pattern_type!`, because current nightly compilers no longer accept the
+//! `rustc_layout_scalar_valid_range_start` and `rustc_layout_scalar_valid_range_end` attributes outside of the
+//! standard library.
+//!
+//! This module requires the `nightly` feature and a nightly compiler, and may break with any nightly release.
+//!
+//! ```rust
+//! use nonany::nightly::NonMaxU32;
+//!
+//! let value = NonMaxU32::new(5).unwrap();
+//! assert_eq!(value.get(), 5);
+//! assert_eq!(unsafe { core::mem::transmute::<NonMaxU32, u32>(value) }, 5);
+//! assert_eq!(core::mem::size_of::<Option<NonMaxU32>>(), core::mem::size_of::<u32>());
+//!
+//! let xor: nonany::NonMaxU32 = value.into();
+//! assert_eq!(NonMaxU32::from(xor), value);
+//! ```
+
+use core::pattern_type;
+use crate::{decimal_len, sealed, CheckedError, CheckedErrorWith, NonAny, ParseNonAnyError, Value};
+use crate::varint::{DecodeError, Varint};
+
+const ISIZE_MIN_PLUS_ONE: isize = isize::MIN + 1;
+const ISIZE_MAX_MINUS_ONE: isize = isize::MAX - 1;
+const USIZE_MAX_MINUS_ONE: usize = usize::MAX - 1;
+
+macro_rules! nonany_nightly {
+    ($name:ident, $int:ident, $niche:expr, $signed:ident, $($range:tt)+) => {
+        #[doc = concat!("An integer that is known not to equal `", stringify!($niche), "`, and is stored as is.")]
+        ///
+        #[doc = concat!("This is the same as [`crate::", stringify!($name), "`], but without the XOR encoding.")]
+        #[derive(Clone, Copy)]
+        #[repr(transparent)]
+        pub struct $name(pattern_type!($int is $($range)+));
+
+        // Pattern types do not implement `PartialEq`, so it cannot be derived. Equality is still the equality of the
+        // value, which makes constants of this type usable in patterns.
+        impl core::marker::StructuralPartialEq for $name {}
+
+        impl core::cmp::PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.get() == other.get()
+            }
+        }
+
+        impl core::cmp::Eq for $name {}
+
+        impl $name {
+            /// The niche value of this integer type.
+            pub const NICHE: $int = $niche;
+
+            /// The size of this integer type in bits.
+            pub const BITS: u32 = <$int>::BITS;
+
+            /// The maximum length of the decimal representation of this integer type, including the sign.
+            pub const MAX_DECIMAL_LEN: usize = crate::$name::MAX_DECIMAL_LEN;
+
+            /// The maximum length of the hexadecimal representation of this integer type, excluding the `0x` prefix.
+            pub const MAX_HEX_LEN: usize = crate::$name::MAX_HEX_LEN;
+
+            /// Creates a non-any if the given value is not `NICHE`.
+            pub const fn new(value: $int) -> Option<Self> {
+                if value == Self::NICHE {
+                    None
+                } else {
+                    // SAFETY: The value is not the niche.
+                    Some(unsafe { Self::new_unchecked(value) })
+                }
+            }
+
+            /// Creates a non-any without checking whether the value is `NICHE`.
+            /// This results in undefined behaviour if the value is `NICHE`.
+            ///
+            /// # Safety
+            ///
+            /// The value must not be `NICHE`.
+            pub const unsafe fn new_unchecked(value: $int) -> Self {
+                core::mem::transmute::<$int, Self>(value)
+            }
+
+            /// Creates a non-any from a constant, which fails to compile if the constant is `NICHE`.
+            pub const fn new_const<const VALUE: $int>() -> Self {
+                Self::from_xor(crate::$name::new_const::<VALUE>())
+            }
+
+            /// Converts a value like `try_from`, but returns the value and the niche along with the error.
+            pub fn try_from_with<T: Copy>(value: T) -> Result<Self, CheckedErrorWith<T, $int>>
+            where
+                crate::$name: core::convert::TryFrom<T, Error = CheckedError>,
+            {
+                crate::$name::try_from_with(value).map(Self::from_xor)
+            }
+
+            /// Returns the contained value as a primitive type.
+            pub const fn get(self) -> $int {
+                // SAFETY: The pattern type has the same layout as the integer.
+                unsafe { core::mem::transmute::<Self, $int>(self) }
+            }
+
+            #[doc = concat!("Converts the value to the XOR encoded [`crate::", stringify!($name), "`].")]
+            pub const fn to_xor(self) -> crate::$name {
+                // SAFETY: Both types have the same niche.
+                unsafe { crate::$name::new_unchecked(self.get()) }
+            }
+
+            #[doc = concat!("Converts a value of the XOR encoded [`crate::", stringify!($name), "`].")]
+            pub const fn from_xor(value: crate::$name) -> Self {
+                // SAFETY: Both types have the same niche.
+                unsafe { Self::new_unchecked(value.get()) }
+            }
+
+            /// Returns `true` if the value equals the value of `other`, which can be a primitive integer, or a non-any
+            /// of any niche, width and encoding.
+            pub fn value_eq<T: Value>(self, other: T) -> bool {
+                self.to_xor().value_eq(other)
+            }
+
+            /// Compares the value with the value of `other`, which can be a primitive integer, or a non-any of any
+            /// niche, width and encoding.
+            pub fn value_cmp<T: Value>(self, other: T) -> core::cmp::Ordering {
+                self.to_xor().value_cmp(other)
+            }
+
+            /// Parses a non-any from a string slice with digits in the given base.
+            pub const fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseNonAnyError> {
+                Self::from_ascii_radix(src.as_bytes(), radix)
+            }
+
+            /// Parses a non-any from an ASCII byte slice with digits in the given base.
+            pub const fn from_ascii_radix(src: &[u8], radix: u32) -> Result<Self, ParseNonAnyError> {
+                match crate::$name::from_ascii_radix(src, radix) {
+                    Ok(value) => Ok(Self::from_xor(value)),
+                    Err(err) => Err(err)
+                }
+            }
+
+            /// Writes the value in decimal to the end of the buffer without using `core::fmt`.
+            pub fn write_decimal(self, buffer: &mut [u8; decimal_len(<$int>::MAX as u128, <$int>::MIN != 0)]) -> &str {
+                self.to_xor().write_decimal(buffer)
+            }
+
+            /// Writes the value in lowercase hexadecimal to the end of the buffer without using `core::fmt`.
+            pub fn write_lower_hex(self, buffer: &mut [u8; <$int>::BITS as usize / 4]) -> &str {
+                self.to_xor().write_lower_hex(buffer)
+            }
+
+            /// Writes the value in uppercase hexadecimal to the end of the buffer without using `core::fmt`.
+            pub fn write_upper_hex(self, buffer: &mut [u8; <$int>::BITS as usize / 4]) -> &str {
+                self.to_xor().write_upper_hex(buffer)
+            }
+
+            nonany_nightly!(@$signed);
+        }
+
+        impl sealed::Sealed for $name {}
+
+        impl NonAny for $name {
+            type Int = $int;
+
+            const NICHE: $int = $niche;
+
+            fn new(value: $int) -> Option<Self> {
+                Self::new(value)
+            }
+
+            unsafe fn new_unchecked(value: $int) -> Self {
+                Self::new_unchecked(value)
+            }
+
+            fn get(self) -> $int {
+                self.get()
+            }
+        }
+
+        impl From<crate::$name> for $name {
+            fn from(value: crate::$name) -> Self {
+                Self::from_xor(value)
+            }
+        }
+
+        impl From<$name> for crate::$name {
+            fn from(value: $name) -> Self {
+                value.to_xor()
+            }
+        }
+
+        nonany_nightly!(@tryfrom, $name, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+        nonany_nightly!(@tryfrom, $name, core::num::NonZeroI8, core::num::NonZeroI16, core::num::NonZeroI32, core::num::NonZeroI64, core::num::NonZeroI128, core::num::NonZeroIsize);
+        nonany_nightly!(@tryfrom, $name, core::num::NonZeroU8, core::num::NonZeroU16, core::num::NonZeroU32, core::num::NonZeroU64, core::num::NonZeroU128, core::num::NonZeroUsize);
+        nonany_nightly!(@tryfrom_nonany, $name, $int, NonAnyI8 i8, NonAnyI16 i16, NonAnyI32 i32, NonAnyI64 i64, NonAnyI128 i128, NonAnyIsize isize);
+        nonany_nightly!(@tryfrom_nonany, $name, $int, NonAnyU8 u8, NonAnyU16 u16, NonAnyU32 u32, NonAnyU64 u64, NonAnyU128 u128, NonAnyUsize usize);
+
+        impl core::cmp::PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl core::cmp::Ord for $name {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.get().cmp(&other.get())
+            }
+        }
+
+        impl core::hash::Hash for $name {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.get().hash(state)
+            }
+        }
+
+        impl core::str::FromStr for $name {
+            type Err = ParseNonAnyError;
+
+            fn from_str(src: &str) -> Result<Self, Self::Err> {
+                src.parse::<crate::$name>().map(Self::from_xor)
+            }
+        }
+
+        nonany_nightly!(@fmt, $name, Debug, Display, UpperHex, LowerHex, Octal, Binary, LowerExp, UpperExp);
+
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.to_xor().serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::$name::deserialize(deserializer).map(Self::from_xor)
+            }
+        }
+
+        // SAFETY: The type is `repr(transparent)` over the integer, which has no padding or uninitialized bytes.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::NoUninit for $name {}
+
+        // SAFETY: Any value other than the niche is valid, and the type has the same layout as the integer.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::CheckedBitPattern for $name {
+            type Bits = $int;
+
+            fn is_valid_bit_pattern(bits: &$int) -> bool {
+                *bits != $niche
+            }
+        }
+
+        // SAFETY: `Option<Self>` uses the niche as `None`, so zero is either `None` or a valid value.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::ZeroableInOption for $name {}
+
+        // SAFETY: Every bit pattern of `Option<Self>` is either the niche or a valid value, and there is no padding.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl ::bytemuck::PodInOption for $name {}
+
+        #[cfg(feature = "equivalent")]
+        impl ::equivalent::Equivalent<$name> for $int {
+            fn equivalent(&self, key: &$name) -> bool {
+                *self == key.get()
+            }
+        }
+
+        #[cfg(feature = "equivalent")]
+        impl<T: NonAny<Int = $int>> ::equivalent::Equivalent<$name> for crate::equivalent::ByValue<T> {
+            fn equivalent(&self, key: &$name) -> bool {
+                self.0.get() == key.get()
+            }
+        }
+
+        impl crate::varint::sealed::Sealed for $name {}
+        impl crate::varint::sealed::Sealed for Option<$name> {}
+
+        impl Varint for $name {
+            const MAX_LEN: usize = <crate::$name as Varint>::MAX_LEN;
+
+            fn encode(self, buffer: &mut [u8]) -> Option<usize> {
+                self.to_xor().encode(buffer)
+            }
+
+            fn decode(buffer: &[u8]) -> Result<(Self, usize), DecodeError> {
+                crate::$name::decode(buffer).map(|(value, len)| (Self::from_xor(value), len))
+            }
+        }
+
+        impl Varint for Option<$name> {
+            const MAX_LEN: usize = <Option<crate::$name> as Varint>::MAX_LEN;
+
+            fn encode(self, buffer: &mut [u8]) -> Option<usize> {
+                self.map($name::to_xor).encode(buffer)
+            }
+
+            fn decode(buffer: &[u8]) -> Result<(Self, usize), DecodeError> {
+                <Option<crate::$name>>::decode(buffer).map(|(value, len)| (value.map($name::from_xor), len))
+            }
+        }
+    };
+
+    (@signed) => {
+        pub const fn abs(self) -> Option<Self> {
+            Self::new(self.get().abs())
+        }
+
+        pub const fn checked_abs(self) -> Result<Self, CheckedError> {
+            match self.to_xor().checked_abs() {
+                Ok(value) => Ok(Self::from_xor(value)),
+                Err(err) => Err(err)
+            }
+        }
+
+        pub const fn is_positive(self) -> bool {
+            self.get().is_positive()
+        }
+
+        pub const fn is_negative(self) -> bool {
+            self.get().is_negative()
+        }
+    };
+
+    (@unsigned) => {};
+
+    (@tryfrom, $name:ident, $($from:ty),+) => {
+        $(
+            impl core::convert::TryFrom<$from> for $name {
+                type Error = CheckedError;
+
+                fn try_from(value: $from) -> Result<Self, Self::Error> {
+                    crate::$name::try_from(value).map(Self::from_xor)
+                }
+            }
+        )+
+    };
+
+    (@tryfrom_nonany, $name:ident, $int:ident, $($nonany:ident $from:ident),+) => {
+        $(
+            nonany_nightly!(@tryfrom_nonany, $name, $int, $nonany, $from);
+        )+
+    };
+
+    // Conversions from the same width go through `From` instead.
+    (@tryfrom_nonany, $name:ident, i8, NonAnyI8, i8) => {};
+    (@tryfrom_nonany, $name:ident, i16, NonAnyI16, i16) => {};
+    (@tryfrom_nonany, $name:ident, i32, NonAnyI32, i32) => {};
+    (@tryfrom_nonany, $name:ident, i64, NonAnyI64, i64) => {};
+    (@tryfrom_nonany, $name:ident, i128, NonAnyI128, i128) => {};
+    (@tryfrom_nonany, $name:ident, isize, NonAnyIsize, isize) => {};
+    (@tryfrom_nonany, $name:ident, u8, NonAnyU8, u8) => {};
+    (@tryfrom_nonany, $name:ident, u16, NonAnyU16, u16) => {};
+    (@tryfrom_nonany, $name:ident, u32, NonAnyU32, u32) => {};
+    (@tryfrom_nonany, $name:ident, u64, NonAnyU64, u64) => {};
+    (@tryfrom_nonany, $name:ident, u128, NonAnyU128, u128) => {};
+    (@tryfrom_nonany, $name:ident, usize, NonAnyUsize, usize) => {};
+
+    (@tryfrom_nonany, $name:ident, $int:ident, $nonany:ident, $from:ident) => {
+        impl<const NICHE: $from> core::convert::TryFrom<crate::$nonany<NICHE>> for $name {
+            type Error = CheckedError;
+
+            fn try_from(value: crate::$nonany<NICHE>) -> Result<Self, Self::Error> {
+                crate::$name::try_from(value).map(Self::from_xor)
+            }
+        }
+    };
+
+    (@fmt, $name:ident, $($trait:ident),+) => {
+        $(
+            impl core::fmt::$trait for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    core::fmt::$trait::fmt(&self.get(), f)
+                }
+            }
+        )+
+    };
+}
+
+nonany_nightly!(NonMinI8, i8, i8::MIN, signed, -127..=i8::MAX);
+nonany_nightly!(NonMinI16, i16, i16::MIN, signed, -32767..=i16::MAX);
+nonany_nightly!(NonMinI32, i32, i32::MIN, signed, -2147483647..=i32::MAX);
+nonany_nightly!(NonMinI64, i64, i64::MIN, signed, -9223372036854775807..=i64::MAX);
+nonany_nightly!(NonMinI128, i128, i128::MIN, signed, -170141183460469231731687303715884105727..=i128::MAX);
+nonany_nightly!(NonMinIsize, isize, isize::MIN, signed, ISIZE_MIN_PLUS_ONE..=isize::MAX);
+
+nonany_nightly!(NonMaxI8, i8, i8::MAX, signed, i8::MIN..=126);
+nonany_nightly!(NonMaxI16, i16, i16::MAX, signed, i16::MIN..=32766);
+nonany_nightly!(NonMaxI32, i32, i32::MAX, signed, i32::MIN..=2147483646);
+nonany_nightly!(NonMaxI64, i64, i64::MAX, signed, i64::MIN..=9223372036854775806);
+nonany_nightly!(NonMaxI128, i128, i128::MAX, signed, i128::MIN..=170141183460469231731687303715884105726);
+nonany_nightly!(NonMaxIsize, isize, isize::MAX, signed, isize::MIN..=ISIZE_MAX_MINUS_ONE);
+
+nonany_nightly!(NonMinU8, u8, u8::MIN, unsigned, 1..=u8::MAX);
+nonany_nightly!(NonMinU16, u16, u16::MIN, unsigned, 1..=u16::MAX);
+nonany_nightly!(NonMinU32, u32, u32::MIN, unsigned, 1..=u32::MAX);
+nonany_nightly!(NonMinU64, u64, u64::MIN, unsigned, 1..=u64::MAX);
+nonany_nightly!(NonMinU128, u128, u128::MIN, unsigned, 1..=u128::MAX);
+nonany_nightly!(NonMinUsize, usize, usize::MIN, unsigned, 1..=usize::MAX);
+
+nonany_nightly!(NonMaxU8, u8, u8::MAX, unsigned, 0..=254);
+nonany_nightly!(NonMaxU16, u16, u16::MAX, unsigned, 0..=65534);
+nonany_nightly!(NonMaxU32, u32, u32::MAX, unsigned, 0..=4294967294);
+nonany_nightly!(NonMaxU64, u64, u64::MAX, unsigned, 0..=18446744073709551614);
+nonany_nightly!(NonMaxU128, u128, u128::MAX, unsigned, 0..=340282366920938463463374607431768211454);
+nonany_nightly!(NonMaxUsize, usize, usize::MAX, unsigned, 0..=USIZE_MAX_MINUS_ONE);
@@ -0,0 +1,116 @@
+//! Integer types with customizable niche values that store `value.wrapping_sub(NICHE)` instead of `value ^ NICHE`.
+//!
+//! Subtracting the niche rotates the values such that the niche becomes zero, which keeps consecutive values
+//! consecutive: incrementing the stored bits increments the value, and the stored bits, read as an unsigned integer,
+//! are ordered like the values starting right after the niche. The downside is that encoding and decoding take an
+//! addition or subtraction instead of a XOR.
+//!
+//! ```rust
+//! use nonany::offset::NonAnyU32;
+//!
+//! let value = NonAnyU32::<100>::new(105).unwrap();
+//! assert_eq!(value.get(), 105);
+//! assert_eq!(unsafe { core::mem::transmute::<NonAnyU32<100>, u32>(value) }, 5);
+//!
+//! let xor: nonany::NonAnyU32<100> = value.into();
+//! assert_eq!(xor.get(), 105);
+//! assert_eq!(NonAnyU32::from(xor), value);
+//! ```
+//!
+//! These types have the same inherent methods and standard traits as the `NonAny*` types in the crate root, implement
+//! [`NonAny`], convert from the primitive integers with `TryFrom`, and convert to and from the XOR encoded types with
+//! `From`. The `TryFrom` conversions from other `NonAny*` and `NonZero*` types, and the `serde`, `bytemuck`,
+//! `equivalent` and `varint` implementations, are only provided for the types in the crate root. Convert to those
+//! with `From` first, which is lossless.
+//!
+//! There is no separate bitwise NOT encoding, because it is the XOR encoding with a niche of all ones.
+//! `NonMaxU*` types, and signed types with a niche of `-1`, already store `!value`.
+
+use crate::{decimal_len, sealed, CheckedError, CheckedErrorWith, NonAny, ParseNonAnyError, ParseNonAnyErrorKind};
+
+nonany!(NonAnyI8, NonZeroI8, i8, signed, offset, "nonany::offset::");
+nonany!(NonAnyI16, NonZeroI16, i16, signed, offset, "nonany::offset::");
+nonany!(NonAnyI32, NonZeroI32, i32, signed, offset, "nonany::offset::");
+nonany!(NonAnyI64, NonZeroI64, i64, signed, offset, "nonany::offset::");
+nonany!(NonAnyI128, NonZeroI128, i128, signed, offset, "nonany::offset::");
+nonany!(NonAnyIsize, NonZeroIsize, isize, signed, offset, "nonany::offset::");
+
+nonany!(NonAnyU8, NonZeroU8, u8, unsigned, offset, "nonany::offset::");
+nonany!(NonAnyU16, NonZeroU16, u16, unsigned, offset, "nonany::offset::");
+nonany!(NonAnyU32, NonZeroU32, u32, unsigned, offset, "nonany::offset::");
+nonany!(NonAnyU64, NonZeroU64, u64, unsigned, offset, "nonany::offset::");
+nonany!(NonAnyU128, NonZeroU128, u128, unsigned, offset, "nonany::offset::");
+nonany!(NonAnyUsize, NonZeroUsize, usize, unsigned, offset, "nonany::offset::");
+
+nonany_tryfrom_int!(NonAnyI8, i8);
+nonany_tryfrom_int!(NonAnyI16, i16);
+nonany_tryfrom_int!(NonAnyI32, i32);
+nonany_tryfrom_int!(NonAnyI64, i64);
+nonany_tryfrom_int!(NonAnyI128, i128);
+nonany_tryfrom_int!(NonAnyIsize, isize);
+
+nonany_tryfrom_int!(NonAnyU8, u8);
+nonany_tryfrom_int!(NonAnyU16, u16);
+nonany_tryfrom_int!(NonAnyU32, u32);
+nonany_tryfrom_int!(NonAnyU64, u64);
+nonany_tryfrom_int!(NonAnyU128, u128);
+nonany_tryfrom_int!(NonAnyUsize, usize);
+
+
+macro_rules! nonany_offset_xor {
+    ($nonany:ident, $int:ident) => {
+        impl<const NICHE: $int> $nonany<NICHE> {
+            /// Converts the value to the XOR encoded type with the same niche.
+            pub const fn to_xor(self) -> crate::$nonany<NICHE> {
+                // SAFETY: The value is not the niche, and both types have the same niche.
+                unsafe { crate::$nonany::new_unchecked(self.get()) }
+            }
+
+            /// Converts a value of the XOR encoded type with the same niche.
+            pub const fn from_xor(value: crate::$nonany<NICHE>) -> Self {
+                // SAFETY: The value is not the niche, and both types have the same niche.
+                unsafe { Self::new_unchecked(value.get()) }
+            }
+        }
+
+        impl<const NICHE: $int> From<crate::$nonany<NICHE>> for $nonany<NICHE> {
+            fn from(value: crate::$nonany<NICHE>) -> Self {
+                Self::from_xor(value)
+            }
+        }
+
+        impl<const NICHE: $int> From<$nonany<NICHE>> for crate::$nonany<NICHE> {
+            fn from(value: $nonany<NICHE>) -> Self {
+                value.to_xor()
+            }
+        }
+    };
+}
+
+nonany_offset_xor!(NonAnyI8, i8);
+nonany_offset_xor!(NonAnyI16, i16);
+nonany_offset_xor!(NonAnyI32, i32);
+nonany_offset_xor!(NonAnyI64, i64);
+nonany_offset_xor!(NonAnyI128, i128);
+nonany_offset_xor!(NonAnyIsize, isize);
+
+nonany_offset_xor!(NonAnyU8, u8);
+nonany_offset_xor!(NonAnyU16, u16);
+nonany_offset_xor!(NonAnyU32, u32);
+nonany_offset_xor!(NonAnyU64, u64);
+nonany_offset_xor!(NonAnyU128, u128);
+nonany_offset_xor!(NonAnyUsize, usize);
+
+nonany_typedef!(NonZeroI8, NonMinI8, NonMaxI8, NonAnyI8, i8);
+nonany_typedef!(NonZeroI16, NonMinI16, NonMaxI16, NonAnyI16, i16);
+nonany_typedef!(NonZeroI32, NonMinI32, NonMaxI32, NonAnyI32, i32);
+nonany_typedef!(NonZeroI64, NonMinI64, NonMaxI64, NonAnyI64, i64);
+nonany_typedef!(NonZeroI128, NonMinI128, NonMaxI128, NonAnyI128, i128);
+nonany_typedef!(NonZeroIsize, NonMinIsize, NonMaxIsize, NonAnyIsize, isize);
+
+nonany_typedef!(NonZeroU8, NonMinU8, NonMaxU8, NonAnyU8, u8);
+nonany_typedef!(NonZeroU16, NonMinU16, NonMaxU16, NonAnyU16, u16);
+nonany_typedef!(NonZeroU32, NonMinU32, NonMaxU32, NonAnyU32, u32);
+nonany_typedef!(NonZeroU64, NonMinU64, NonMaxU64, NonAnyU64, u64);
+nonany_typedef!(NonZeroU128, NonMinU128, NonMaxU128, NonAnyU128, u128);
+nonany_typedef!(NonZeroUsize, NonMinUsize, NonMaxUsize, NonAnyUsize, usize);
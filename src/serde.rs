@@ -0,0 +1,234 @@
+//! [serde](https://serde.rs) support for the `NonAny*` types.
+//!
+//! All `NonAny*` types serialize as their primitive value, and fail to deserialize when the value is the niche.
+//! `NonAnyI128` and `NonAnyU128` use serde's 128-bit integer support. When a format passes a string instead of an
+//! integer, which text based formats without 128-bit integer support often do, every `NonAny*` type deserializes
+//! it with `FromStr`. Formats such as `serde_json` reject a string before it reaches the `NonAny*` type, so for those
+//! the [`string`] module can be used to (de)serialize them as strings instead.
+//!
+//! ```rust
+//! let value: nonany::NonMaxU8 = serde_json::from_str("42").unwrap();
+//! assert_eq!(value.get(), 42);
+//! assert_eq!(serde_json::to_string(&value).unwrap(), "42");
+//!
+//! let err = serde_json::from_str::<nonany::NonMaxU8>("255").unwrap_err();
+//! assert_eq!(err.to_string(), "invalid value: integer `255`, expected a u8 other than the niche 255 at line 1 column 3");
+//!
+//! use serde::de::{value::{Error, StrDeserializer}, Deserialize, IntoDeserializer};
+//!
+//! let deserializer: StrDeserializer<Error> = "340282366920938463463374607431768211454".into_deserializer();
+//! assert_eq!(nonany::NonMaxU128::deserialize(deserializer).unwrap().get(), u128::MAX - 1);
+//! ```
+
+use core::{fmt, marker::PhantomData};
+use ::serde::de::{Deserialize, Deserializer, Error, Unexpected, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+
+struct NonAnyVisitor<T>(PhantomData<T>);
+
+fn unexpected_i128(value: i128) -> Unexpected<'static> {
+    match i64::try_from(value) {
+        Ok(value) => Unexpected::Signed(value),
+        Err(_) => Unexpected::Other("128-bit integer")
+    }
+}
+
+fn unexpected_u128(value: u128) -> Unexpected<'static> {
+    match u64::try_from(value) {
+        Ok(value) => Unexpected::Unsigned(value),
+        Err(_) => Unexpected::Other("128-bit integer")
+    }
+}
+
+macro_rules! nonany_serde {
+    ($nonany:ident, $int:ident, $serialize:ident, $deserialize:ident) => {
+        impl<const NICHE: $int> Serialize for crate::$nonany<NICHE> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.$serialize(self.get() as _)
+            }
+        }
+
+        impl<'de, const NICHE: $int> Deserialize<'de> for crate::$nonany<NICHE> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.$deserialize(NonAnyVisitor::<Self>(PhantomData))
+            }
+        }
+
+        impl<'de, const NICHE: $int> Visitor<'de> for NonAnyVisitor<crate::$nonany<NICHE>> {
+            type Value = crate::$nonany<NICHE>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!("a ", stringify!($int), " other than the niche {}"), NICHE)
+            }
+
+            fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+                Self::Value::try_from(value).map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))
+            }
+
+            fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+                Self::Value::try_from(value).map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+                Self::Value::try_from(value).map_err(|_| E::invalid_value(unexpected_i128(value), &self))
+            }
+
+            fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+                Self::Value::try_from(value).map_err(|_| E::invalid_value(unexpected_u128(value), &self))
+            }
+
+            fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+    };
+}
+
+nonany_serde!(NonAnyI8, i8, serialize_i8, deserialize_i8);
+nonany_serde!(NonAnyI16, i16, serialize_i16, deserialize_i16);
+nonany_serde!(NonAnyI32, i32, serialize_i32, deserialize_i32);
+nonany_serde!(NonAnyI64, i64, serialize_i64, deserialize_i64);
+nonany_serde!(NonAnyI128, i128, serialize_i128, deserialize_i128);
+
+nonany_serde!(NonAnyU8, u8, serialize_u8, deserialize_u8);
+nonany_serde!(NonAnyU16, u16, serialize_u16, deserialize_u16);
+nonany_serde!(NonAnyU32, u32, serialize_u32, deserialize_u32);
+nonany_serde!(NonAnyU64, u64, serialize_u64, deserialize_u64);
+nonany_serde!(NonAnyU128, u128, serialize_u128, deserialize_u128);
+
+// Like the primitive types, pointer sized integers are always serialized as 64-bit integers.
+nonany_serde!(NonAnyIsize, isize, serialize_i64, deserialize_i64);
+nonany_serde!(NonAnyUsize, usize, serialize_u64, deserialize_u64);
+
+/// (De)serializes values as strings, for use with `#[serde(with = "nonany::serde::string")]`.
+///
+/// This is mainly useful for `NonAnyI128` and `NonAnyU128` in formats that don't support 128-bit integers.
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Counter {
+///     #[serde(with = "nonany::serde::string")]
+///     total: nonany::NonMaxU128,
+/// }
+///
+/// let counter: Counter = serde_json::from_str(r#"{"total":"12345"}"#).unwrap();
+/// assert_eq!(counter.total.get(), 12345);
+/// assert_eq!(serde_json::to_string(&counter).unwrap(), r#"{"total":"12345"}"#);
+/// ```
+pub mod string {
+    use core::{fmt, marker::PhantomData, str::FromStr};
+    use ::serde::de::{Deserializer, Error, Visitor};
+    use ::serde::ser::Serializer;
+
+    /// Serializes a value as a string using its `Display` implementation.
+    pub fn serialize<T: fmt::Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    /// Deserializes a value from a string using its `FromStr` implementation.
+    pub fn deserialize<'de, T: FromStr, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>
+    where
+        T::Err: fmt::Display,
+    {
+        struct StringVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: FromStr> Visitor<'de> for StringVisitor<T>
+        where
+            T::Err: fmt::Display,
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string containing an integer")
+            }
+
+            fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(StringVisitor(PhantomData))
+    }
+}
+
+/// (De)serializes an `Option` of a `NonAny*` type as its raw sentinel value, for use with
+/// `#[serde(with = "nonany::serde::sentinel")]`.
+///
+/// `None` is serialized as the niche, and any other value is serialized as itself. Deserializing the niche
+/// results in `None`. This matches wire formats that encode missing values as `-1` or `0xFFFFFFFF` instead of `null`.
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Process {
+///     #[serde(with = "nonany::serde::sentinel")]
+///     parent: Option<nonany::NonAnyI32<-1>>,
+/// }
+///
+/// let process: Process = serde_json::from_str(r#"{"parent":-1}"#).unwrap();
+/// assert_eq!(process.parent, None);
+/// assert_eq!(serde_json::to_string(&process).unwrap(), r#"{"parent":-1}"#);
+/// ```
+pub mod sentinel {
+    use ::serde::de::{Deserialize, Deserializer};
+    use ::serde::ser::{Serialize, Serializer};
+    use crate::NonAny;
+
+    /// Serializes `None` as the niche, and any other value as itself.
+    pub fn serialize<T: NonAny, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T::Int: Serialize,
+    {
+        match value {
+            Some(value) => value.get().serialize(serializer),
+            None => T::NICHE.serialize(serializer)
+        }
+    }
+
+    /// Deserializes the niche as `None`, and any other value as itself.
+    pub fn deserialize<'de, T: NonAny, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T::Int: Deserialize<'de>,
+    {
+        T::Int::deserialize(deserializer).map(T::new)
+    }
+}
+
+/// Like [`sentinel`], but also accepts `null` as `None` when deserializing, for use with
+/// `#[serde(with = "nonany::serde::null_or_sentinel")]`.
+///
+/// `None` is still serialized as the niche.
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Stream {
+///     #[serde(with = "nonany::serde::null_or_sentinel")]
+///     id: Option<nonany::NonMaxU32>,
+/// }
+///
+/// let a: Stream = serde_json::from_str(r#"{"id":null}"#).unwrap();
+/// let b: Stream = serde_json::from_str(r#"{"id":4294967295}"#).unwrap();
+/// assert_eq!(a.id, None);
+/// assert_eq!(b.id, None);
+/// assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"id":4294967295}"#);
+/// ```
+pub mod null_or_sentinel {
+    use ::serde::de::{Deserialize, Deserializer};
+    use ::serde::ser::{Serialize, Serializer};
+    use crate::NonAny;
+
+    /// Serializes `None` as the niche, and any other value as itself.
+    pub fn serialize<T: NonAny, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T::Int: Serialize,
+    {
+        super::sentinel::serialize(value, serializer)
+    }
+
+    /// Deserializes `null` and the niche as `None`, and any other value as itself.
+    pub fn deserialize<'de, T: NonAny, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T::Int: Deserialize<'de>,
+    {
+        Option::<T::Int>::deserialize(deserializer).map(|value| value.and_then(T::new))
+    }
+}
@@ -30,6 +30,7 @@ impl Write for FormatBuffer {
         }
 
         self.buffer[self.offset..end].copy_from_slice(bytes);
+        self.offset = end;
 
         Ok(())
     }
@@ -60,7 +61,7 @@ macro_rules! test_nonany {
             type NonAny = crate::$nonany::<{ NICHE }>;
 
             assert_eq!(NonAny::try_from(256), Err($crate::CheckedError::Overflow));
-            assert_eq!(NonAny::try_from(-256), Err($crate::CheckedError::Overflow));
+            assert_eq!(NonAny::try_from(-256), Err($crate::CheckedError::Underflow));
 
             for i in $int::MIN..=$int::MAX {
                 if i == NICHE {
@@ -175,6 +176,7 @@ macro_rules! test_nonany {
                 Ok(_) => panic!(),
                 Err(err) => match err {
                     crate::CheckedError::Overflow => (),
+                    crate::CheckedError::Underflow => panic!(),
                     crate::CheckedError::Niche => panic!()
                 }
             };
@@ -183,6 +185,7 @@ macro_rules! test_nonany {
                 Ok(_) => panic!(),
                 Err(err) => match err {
                     crate::CheckedError::Overflow => panic!(),
+                    crate::CheckedError::Underflow => panic!(),
                     crate::CheckedError::Niche => ()
                 }
             };
@@ -191,6 +194,7 @@ macro_rules! test_nonany {
                 Ok(abs) => abs,
                 Err(err) => match err {
                     crate::CheckedError::Overflow => panic!(),
+                    crate::CheckedError::Underflow => panic!(),
                     crate::CheckedError::Niche => panic!()
                 }
             };
@@ -243,6 +247,24 @@ test_size!(crate::NonMaxU64, u64);
 test_size!(crate::NonMaxU128, u128);
 test_size!(crate::NonMaxUsize, usize);
 
+test_size!(crate::NonZeroI16Be, i16);
+test_size!(crate::NonZeroI32Be, i32);
+test_size!(crate::NonZeroI64Be, i64);
+test_size!(crate::NonZeroI128Be, i128);
+test_size!(crate::NonZeroU16Be, u16);
+test_size!(crate::NonZeroU32Be, u32);
+test_size!(crate::NonZeroU64Be, u64);
+test_size!(crate::NonZeroU128Be, u128);
+
+test_size!(crate::NonZeroI16Le, i16);
+test_size!(crate::NonZeroI32Le, i32);
+test_size!(crate::NonZeroI64Le, i64);
+test_size!(crate::NonZeroI128Le, i128);
+test_size!(crate::NonZeroU16Le, u16);
+test_size!(crate::NonZeroU32Le, u32);
+test_size!(crate::NonZeroU64Le, u64);
+test_size!(crate::NonZeroU128Le, u128);
+
 macro_rules! test_from_int {
     ($nonany:ident) => {
         const _: () = assert!(impls!($crate::$nonany: !From<i8> & !From<i16> & !From<i32> & !From<i64> & !From<i128> & !From<isize>));
@@ -782,3 +804,1702 @@ test_nonany!(u8, 252u8, test_nonanyu_252);
 test_nonany!(u8, 253u8, test_nonanyu_253);
 test_nonany!(u8, 254u8, test_nonanyu_254);
 test_nonany!(u8, 255u8, test_nonanyu_255);
+
+const _: () = {
+    const MIN: crate::NonMinI32 = crate::nonany_lit!(crate::NonMinI32, i32::MAX);
+    const MAX: crate::NonMaxU8 = crate::nonany_lit!(crate::NonMaxU8, 0);
+    const ANY: crate::NonAnyI16<-1> = crate::NonAnyI16::<-1>::new_const::<1>();
+
+    assert!(MIN.get() == i32::MAX);
+    assert!(MAX.get() == 0);
+    assert!(ANY.get() == 1);
+};
+
+#[test]
+fn test_checked_error() {
+    assert_eq!(crate::NonAnyU8::<0>::try_from(-1i64), Err(crate::CheckedError::Underflow));
+    assert_eq!(crate::NonAnyU8::<0>::try_from(256i64), Err(crate::CheckedError::Overflow));
+    assert_eq!(crate::NonAnyI8::<0>::try_from(crate::NonMaxI16::new(-129).unwrap()), Err(crate::CheckedError::Underflow));
+    assert_eq!(crate::NonAnyU64::<0>::try_from(core::num::NonZeroI32::new(-1).unwrap()), Err(crate::CheckedError::Underflow));
+    assert_eq!(crate::NonAnyI128::<0>::try_from(u128::MAX), Err(crate::CheckedError::Overflow));
+
+    let err = crate::NonAnyU8::<7>::try_from_with(7u64).unwrap_err();
+    assert_eq!(err.error(), crate::CheckedError::Niche);
+    assert_eq!(err.value(), 7u64);
+    assert_eq!(err.niche(), 7u8);
+    assert_eq!(crate::CheckedError::from(err), crate::CheckedError::Niche);
+
+    let mut buffer = FormatBuffer::default();
+    core::write!(buffer, "{}", err).unwrap();
+    assert_eq!(buffer.as_str(), "value is equal to the niche of the target type (value: 7, niche: 7)");
+
+    assert_eq!(crate::CheckedError::from(u8::try_from(-1i32).unwrap_err()), crate::CheckedError::Overflow);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_error_trait() {
+    use std::{boxed::Box, error::Error};
+
+    let err: Box<dyn Error> = Box::new(crate::NonAnyU8::<7>::try_from_with(7u64).unwrap_err());
+    assert_eq!(err.source().map(|source| source.is::<crate::CheckedError>()), Some(true));
+
+    let err: Box<dyn Error> = Box::new("".parse::<crate::NonMaxU8>().unwrap_err());
+    assert!(err.source().is_none());
+}
+
+macro_rules! test_parse {
+    ($nonany:ident, $int:ident) => {
+        {
+            type NonAny = crate::$nonany<0>;
+            let kind = |src: &str| src.parse::<NonAny>().map_err(|err| *err.kind());
+
+            assert_eq!(kind(""), Err(crate::ParseNonAnyErrorKind::Empty));
+            assert_eq!(kind("+"), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+            assert_eq!(kind("0x"), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+            assert_eq!(kind("12a"), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+            assert_eq!(kind("0"), Err(crate::ParseNonAnyErrorKind::Niche));
+            assert_eq!(kind("0x0"), Err(crate::ParseNonAnyErrorKind::Niche));
+            assert_eq!(kind("+1"), Ok(NonAny::new(1).unwrap()));
+            assert_eq!(kind("0x1f"), Ok(NonAny::new(31).unwrap()));
+            assert_eq!(kind("0o17"), Ok(NonAny::new(15).unwrap()));
+            assert_eq!(kind("0b101"), Ok(NonAny::new(5).unwrap()));
+            assert_eq!(kind("0X1f"), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+
+            let mut buffer = FormatBuffer::default();
+            core::write!(buffer, "{}0", $int::MAX).unwrap();
+            assert_eq!(kind(buffer.as_str()), Err(crate::ParseNonAnyErrorKind::PosOverflow));
+
+            let mut buffer = FormatBuffer::default();
+            core::write!(buffer, "{}", $int::MAX).unwrap();
+            assert_eq!(kind(buffer.as_str()), Ok(NonAny::new($int::MAX).unwrap()));
+
+            let mut buffer = FormatBuffer::default();
+            core::write!(buffer, "{}", $int::MIN).unwrap();
+            if $int::MIN == 0 {
+                assert_eq!(kind(buffer.as_str()), Err(crate::ParseNonAnyErrorKind::Niche));
+                assert_eq!(kind("-1"), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+            } else {
+                assert_eq!(kind(buffer.as_str()), Ok(NonAny::new($int::MIN).unwrap()));
+
+                let mut buffer = FormatBuffer::default();
+                core::write!(buffer, "{}0", $int::MIN).unwrap();
+                assert_eq!(kind(buffer.as_str()), Err(crate::ParseNonAnyErrorKind::NegOverflow));
+            }
+
+            assert_eq!(NonAny::from_str_radix("zz", 36).map(NonAny::get), Ok(35 * 36 + 35));
+            assert_eq!(NonAny::from_str_radix("1", 1).map_err(|err| *err.kind()), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+            assert_eq!(NonAny::from_str_radix("1", 37).map_err(|err| *err.kind()), Err(crate::ParseNonAnyErrorKind::InvalidDigit));
+            assert_eq!(NonAny::from_str_radix("0b1", 16).map(NonAny::get), Ok(0xb1));
+        }
+    };
+}
+
+macro_rules! test_parse_roundtrip {
+    ($nonany:ident, $int:ident) => {
+        {
+            type NonAny = crate::$nonany<7>;
+
+            assert_eq!("7".parse::<NonAny>().map_err(|err| *err.kind()), Err(crate::ParseNonAnyErrorKind::Niche));
+            assert_eq!(NonAny::from_str_radix("0x7", 16).map_err(|err| *err.kind()), Err(crate::ParseNonAnyErrorKind::Niche));
+
+            for value in [$int::MIN, $int::MIN + 1, 0, 6, 8, $int::MAX - 1, $int::MAX] {
+                let non = NonAny::new(value).unwrap();
+
+                let mut buffer = FormatBuffer::default();
+                core::write!(buffer, "{}", non).unwrap();
+                assert_eq!(buffer.as_str().parse::<NonAny>(), Ok(non));
+                assert_eq!(NonAny::from_str_radix(buffer.as_str(), 10), Ok(non));
+                assert_eq!(NonAny::from_ascii_radix(buffer.as_str().as_bytes(), 10), Ok(non));
+
+                #[allow(unused_comparisons)]
+                let non_negative = value >= 0;
+                if non_negative {
+                    macro_rules! test_parse_radix {
+                        ($format:literal, $radix:literal) => {
+                            let mut buffer = FormatBuffer::default();
+                            core::write!(buffer, $format, non).unwrap();
+                            assert_eq!(NonAny::from_str_radix(buffer.as_str(), $radix), Ok(non));
+                        };
+                    }
+
+                    test_parse_radix!("{:x}", 16);
+                    test_parse_radix!("{:#x}", 16);
+                    test_parse_radix!("{:X}", 16);
+                    test_parse_radix!("{:o}", 8);
+                    test_parse_radix!("{:#o}", 8);
+                    test_parse_radix!("{:b}", 2);
+                    test_parse_radix!("{:#b}", 2);
+
+                    let mut buffer = FormatBuffer::default();
+                    core::write!(buffer, "{:#x}", non).unwrap();
+                    assert_eq!(buffer.as_str().parse::<NonAny>(), Ok(non));
+                }
+            }
+        }
+    };
+}
+
+#[test]
+fn test_parse_roundtrip() {
+    test_parse_roundtrip!(NonAnyI8, i8);
+    test_parse_roundtrip!(NonAnyI16, i16);
+    test_parse_roundtrip!(NonAnyI32, i32);
+    test_parse_roundtrip!(NonAnyI64, i64);
+    test_parse_roundtrip!(NonAnyI128, i128);
+    test_parse_roundtrip!(NonAnyIsize, isize);
+
+    test_parse_roundtrip!(NonAnyU8, u8);
+    test_parse_roundtrip!(NonAnyU16, u16);
+    test_parse_roundtrip!(NonAnyU32, u32);
+    test_parse_roundtrip!(NonAnyU64, u64);
+    test_parse_roundtrip!(NonAnyU128, u128);
+    test_parse_roundtrip!(NonAnyUsize, usize);
+}
+
+#[test]
+fn test_parse() {
+    test_parse!(NonAnyI16, i16);
+    test_parse!(NonAnyI32, i32);
+    test_parse!(NonAnyI64, i64);
+    test_parse!(NonAnyI128, i128);
+    test_parse!(NonAnyIsize, isize);
+
+    test_parse!(NonAnyU16, u16);
+    test_parse!(NonAnyU32, u32);
+    test_parse!(NonAnyU64, u64);
+    test_parse!(NonAnyU128, u128);
+    test_parse!(NonAnyUsize, usize);
+}
+
+const _: () = {
+    match crate::NonMaxU32::from_str_radix("-1", 10) {
+        Ok(_) => panic!(),
+        Err(err) => assert!(matches!(err.kind(), crate::ParseNonAnyErrorKind::InvalidDigit))
+    }
+    match crate::NonMinI8::from_ascii_radix(b"-0x7f", 16) {
+        Ok(non) => assert!(non.get() == -127),
+        Err(_) => panic!()
+    }
+};
+
+macro_rules! test_write {
+    ($nonany:ident, $int:ident) => {
+        for value in [$int::MIN, $int::MIN + 1, $int::MIN / 3, 1, 6, 8, 9, 10, 15, 16, 99, 100, $int::MAX / 3, $int::MAX - 1, $int::MAX] {
+            let non = crate::$nonany::<7>::new(value).unwrap();
+
+            let mut buffer = FormatBuffer::default();
+            core::write!(buffer, "{}", value).unwrap();
+            assert_eq!(non.write_decimal(&mut [0; crate::$nonany::<0>::MAX_DECIMAL_LEN]), buffer.as_str());
+
+            let mut buffer = FormatBuffer::default();
+            core::write!(buffer, "{:x}", value).unwrap();
+            assert_eq!(non.write_lower_hex(&mut [0; crate::$nonany::<0>::MAX_HEX_LEN]), buffer.as_str());
+
+            let mut buffer = FormatBuffer::default();
+            core::write!(buffer, "{:X}", value).unwrap();
+            assert_eq!(non.write_upper_hex(&mut [0; crate::$nonany::<0>::MAX_HEX_LEN]), buffer.as_str());
+        }
+
+        let mut buffer = FormatBuffer::default();
+        core::write!(buffer, "{}", $int::MIN).unwrap();
+        assert!(buffer.as_str().len() <= crate::$nonany::<0>::MAX_DECIMAL_LEN);
+    };
+}
+
+#[test]
+fn test_write() {
+    test_write!(NonAnyI16, i16);
+    test_write!(NonAnyI32, i32);
+    test_write!(NonAnyI64, i64);
+    test_write!(NonAnyI128, i128);
+    test_write!(NonAnyIsize, isize);
+
+    test_write!(NonAnyU16, u16);
+    test_write!(NonAnyU32, u32);
+    test_write!(NonAnyU64, u64);
+    test_write!(NonAnyU128, u128);
+    test_write!(NonAnyUsize, usize);
+
+    assert_eq!(crate::NonAnyI8::<0>::MAX_DECIMAL_LEN, 4);
+    assert_eq!(crate::NonAnyU64::<0>::MAX_DECIMAL_LEN, 20);
+    assert_eq!(crate::NonAnyI64::<0>::MAX_DECIMAL_LEN, 20);
+    assert_eq!(crate::NonAnyU128::<0>::MAX_DECIMAL_LEN, 39);
+    assert_eq!(crate::NonAnyI128::<0>::MAX_DECIMAL_LEN, 40);
+}
+
+macro_rules! test_endian {
+    ($endian:ident, $nonany:ident, $int:ident, $to:ident) => {
+        {
+            type Endian = crate::$endian;
+            type NonAny = crate::$nonany;
+
+            assert_eq!(core::mem::align_of::<Option<Endian>>(), 1);
+            assert_eq!(Endian::new(0), None);
+            assert_eq!(Endian::try_from(0), Err(crate::CheckedError::Niche));
+            assert_eq!(Endian::from_bytes([0; core::mem::size_of::<$int>()]), None);
+            assert_eq!("0".parse::<Endian>().map_err(|err| *err.kind()), Err(crate::ParseNonAnyErrorKind::Niche));
+
+            for value in [$int::MIN + 1, 1, 6, 8, $int::MAX - 1, $int::MAX] {
+                let endian = Endian::new(value).unwrap();
+                assert_eq!(endian.get(), value);
+                assert_eq!(endian.to_bytes(), value.$to());
+                assert_eq!(Endian::from_bytes(endian.to_bytes()), Some(endian));
+                assert_eq!(Endian::try_from(value), Ok(endian));
+                assert_eq!(NonAny::from(endian), NonAny::new(value).unwrap());
+                assert_eq!(Endian::from(NonAny::new(value).unwrap()), endian);
+                assert_eq!(core::num::$nonany::from(endian), core::num::$nonany::new(value).unwrap());
+                assert_eq!(endian.cmp(&Endian::new(6).unwrap()), value.cmp(&6));
+                assert!(endian.value_eq(value));
+                assert_eq!(endian.value_cmp(6i8), value.cmp(&6));
+
+                let mut buffer = FormatBuffer::default();
+                core::write!(buffer, "{}", value).unwrap();
+                assert_eq!(buffer.as_str().parse::<Endian>(), Ok(endian));
+
+                macro_rules! test_endian_fmt {
+                    ($format:literal) => {
+                        let mut expected = FormatBuffer::default();
+                        core::write!(expected, $format, value).unwrap();
+                        let mut buffer = FormatBuffer::default();
+                        core::write!(buffer, $format, endian).unwrap();
+                        assert_eq!(buffer.as_str(), expected.as_str());
+                    };
+                }
+
+                test_endian_fmt!("{:?}");
+                test_endian_fmt!("{:#b}");
+                test_endian_fmt!("{:o}");
+                test_endian_fmt!("{:x}");
+                test_endian_fmt!("{:08X}");
+            }
+        }
+    };
+}
+
+#[test]
+fn test_endian() {
+    test_endian!(NonZeroI16Be, NonZeroI16, i16, to_be_bytes);
+    test_endian!(NonZeroI32Be, NonZeroI32, i32, to_be_bytes);
+    test_endian!(NonZeroI64Be, NonZeroI64, i64, to_be_bytes);
+    test_endian!(NonZeroI128Be, NonZeroI128, i128, to_be_bytes);
+    test_endian!(NonZeroU16Be, NonZeroU16, u16, to_be_bytes);
+    test_endian!(NonZeroU32Be, NonZeroU32, u32, to_be_bytes);
+    test_endian!(NonZeroU64Be, NonZeroU64, u64, to_be_bytes);
+    test_endian!(NonZeroU128Be, NonZeroU128, u128, to_be_bytes);
+
+    test_endian!(NonZeroI16Le, NonZeroI16, i16, to_le_bytes);
+    test_endian!(NonZeroI32Le, NonZeroI32, i32, to_le_bytes);
+    test_endian!(NonZeroI64Le, NonZeroI64, i64, to_le_bytes);
+    test_endian!(NonZeroI128Le, NonZeroI128, i128, to_le_bytes);
+    test_endian!(NonZeroU16Le, NonZeroU16, u16, to_le_bytes);
+    test_endian!(NonZeroU32Le, NonZeroU32, u32, to_le_bytes);
+    test_endian!(NonZeroU64Le, NonZeroU64, u64, to_le_bytes);
+    test_endian!(NonZeroU128Le, NonZeroU128, u128, to_le_bytes);
+}
+
+macro_rules! test_offset {
+    ($nonany:ident, $int:ident, $uint:ident, $niche:expr, $values:expr) => {
+        {
+            type Offset = crate::offset::$nonany<{ $niche }>;
+            type Xor = crate::$nonany<{ $niche }>;
+
+            assert_eq!(Offset::new($niche), None);
+            assert_eq!(Offset::try_from($niche), Err(crate::CheckedError::Niche));
+
+            for value in $values {
+                if value == $niche {
+                    continue;
+                }
+
+                let offset = Offset::new(value).unwrap();
+                let bits = unsafe { core::mem::transmute::<Offset, $uint>(offset) };
+                assert_eq!(offset.get(), value);
+                assert_eq!(bits, value.wrapping_sub($niche) as $uint);
+                assert_eq!(Offset::try_from(value), Ok(offset));
+                assert_eq!(Offset::from(Xor::new(value).unwrap()), offset);
+                assert_eq!(Xor::from(offset), Xor::new(value).unwrap());
+
+                if let Some(next) = value.checked_add(1).and_then(Offset::new) {
+                    assert_eq!(unsafe { core::mem::transmute::<Offset, $uint>(next) }, bits + 1);
+                    assert!(next > offset);
+                }
+            }
+        }
+    };
+}
+
+#[test]
+fn test_offset() {
+    test_offset!(NonAnyI8, i8, u8, i8::MIN, i8::MIN..=i8::MAX);
+    test_offset!(NonAnyI8, i8, u8, -1, i8::MIN..=i8::MAX);
+    test_offset!(NonAnyI8, i8, u8, 0, i8::MIN..=i8::MAX);
+    test_offset!(NonAnyI8, i8, u8, 7, i8::MIN..=i8::MAX);
+    test_offset!(NonAnyI8, i8, u8, i8::MAX, i8::MIN..=i8::MAX);
+
+    test_offset!(NonAnyU8, u8, u8, 0, u8::MIN..=u8::MAX);
+    test_offset!(NonAnyU8, u8, u8, 7, u8::MIN..=u8::MAX);
+    test_offset!(NonAnyU8, u8, u8, u8::MAX, u8::MIN..=u8::MAX);
+
+    test_offset!(NonAnyI32, i32, u32, 7, [i32::MIN, -1, 0, 6, 8, i32::MAX]);
+    test_offset!(NonAnyI64, i64, u64, i64::MIN, [i64::MIN + 1, -1, 0, i64::MAX]);
+    test_offset!(NonAnyU64, u64, u64, u64::MAX, [0, 1, u64::MAX - 1]);
+    test_offset!(NonAnyU128, u128, u128, 7, [0, 6, 8, u128::MAX]);
+    test_offset!(NonAnyUsize, usize, usize, 7, [0, 6, 8, usize::MAX]);
+}
+
+mod fixed {
+    use core::fmt::Write;
+    use super::FormatBuffer;
+    use crate::CheckedError;
+    use crate::fixed::{FromFloatError, NonAnyFixedI32, NonMaxFixedU64, NonMinFixedI16, NonMinFixedI32, NonZeroFixedU32, RoundingMode};
+
+    const _: () = assert!(core::mem::size_of::<Option<NonMinFixedI32<16>>>() == core::mem::size_of::<i32>());
+    const _: () = assert!(core::mem::size_of::<Option<NonMaxFixedU64<32>>>() == core::mem::size_of::<u64>());
+
+    macro_rules! assert_fmt {
+        ($expected:expr, $($args:tt)+) => {
+            {
+                let mut buffer = FormatBuffer::default();
+                write!(buffer, $($args)+).unwrap();
+                assert_eq!(buffer.as_str(), $expected);
+            }
+        };
+    }
+
+    #[test]
+    fn test_fixed_float() {
+        type Q16 = NonMinFixedI32<16>;
+
+        assert_eq!(Q16::from_f64(1.5, RoundingMode::Nearest).unwrap().to_bits(), 0x1_8000);
+        assert_eq!(Q16::from_f64(-1.5, RoundingMode::Nearest).unwrap().to_bits(), -0x1_8000);
+        assert_eq!(Q16::from_f32(0.25, RoundingMode::Nearest).unwrap().to_f32(), 0.25);
+        assert_eq!(Q16::from_f64(-32768.0, RoundingMode::Nearest), Err(FromFloatError::Checked(CheckedError::Niche)));
+        assert_eq!(Q16::from_f64(-32769.0, RoundingMode::Nearest), Err(FromFloatError::Checked(CheckedError::Underflow)));
+        assert_eq!(Q16::from_f64(32768.0, RoundingMode::Nearest), Err(FromFloatError::Checked(CheckedError::Overflow)));
+        assert_eq!(Q16::from_f64(f64::INFINITY, RoundingMode::Nearest), Err(FromFloatError::Checked(CheckedError::Overflow)));
+        assert_eq!(Q16::from_f64(f64::NAN, RoundingMode::Nearest), Err(FromFloatError::NaN));
+        assert_eq!(Q16::from_f32(f32::NAN, RoundingMode::Nearest), Err(FromFloatError::NaN));
+        assert_eq!(Q16::from_f64(32767.99999, RoundingMode::Down).unwrap().to_bits(), i32::MAX);
+        assert_eq!(Q16::from_f64(32767.99999, RoundingMode::Up), Err(FromFloatError::Checked(CheckedError::Overflow)));
+
+        // Values halfway between two bits, and slightly off.
+        let half = 1.0 / 131072.0;
+        let cases = [
+            (2.5, [2, 2, 3, 3, 2]),
+            (3.5, [3, 3, 4, 4, 4]),
+            (-2.5, [-2, -3, -2, -3, -2]),
+            (-3.5, [-3, -4, -3, -4, -4]),
+            (2.25, [2, 2, 3, 2, 2]),
+            (-2.75, [-2, -3, -2, -3, -3]),
+        ];
+        let modes = [RoundingMode::TowardZero, RoundingMode::Down, RoundingMode::Up, RoundingMode::Nearest, RoundingMode::NearestEven];
+        for (value, expected) in cases {
+            for (mode, expected) in modes.into_iter().zip(expected) {
+                assert_eq!(Q16::from_f64(value * 2.0 * half, mode).unwrap().to_bits(), expected, "{} {:?}", value, mode);
+            }
+        }
+
+        for bits in [i32::MIN + 1, -0x1_0000, -1, 1, 0x1_0000, i32::MAX] {
+            let value = Q16::from_bits(bits).unwrap();
+            assert_eq!(Q16::from_f64(value.to_f64(), RoundingMode::TowardZero), Ok(value));
+        }
+
+        type Q0 = NonMaxFixedU64<64>;
+        assert_eq!(Q0::from_f64(0.5, RoundingMode::Nearest).unwrap().to_bits(), 1 << 63);
+        assert_eq!(Q0::from_f64(1.0, RoundingMode::Nearest), Err(FromFloatError::Checked(CheckedError::Overflow)));
+        assert_eq!(Q0::from_f64(-0.1, RoundingMode::Nearest), Err(FromFloatError::Checked(CheckedError::Underflow)));
+        assert_eq!(Q0::from_f64(-1e-30, RoundingMode::TowardZero).unwrap().to_bits(), 0);
+    }
+
+    #[test]
+    fn test_fixed_arithmetic() {
+        type Q16 = NonMinFixedI32<16>;
+        let q = |value: f64| Q16::from_f64(value, RoundingMode::Nearest).unwrap();
+
+        assert_eq!(Q16::from_int(3), Ok(q(3.0)));
+        assert_eq!(Q16::from_int(-32768), Err(CheckedError::Niche));
+        assert_eq!(Q16::from_int(32768), Err(CheckedError::Overflow));
+        assert_eq!(q(1.5).checked_add(q(2.25)), Ok(q(3.75)));
+        assert_eq!(q(1.5).checked_sub(q(2.25)), Ok(q(-0.75)));
+        assert_eq!(q(1.5).checked_mul(q(-2.25)), Ok(q(-3.375)));
+        assert_eq!(q(-3.375).checked_div(q(1.5)), Ok(q(-2.25)));
+        assert_eq!(q(1.0).checked_div(q(0.0)), Err(CheckedError::Overflow));
+        assert_eq!(q(-16384.0).checked_add(q(-16384.0)), Err(CheckedError::Niche));
+        assert_eq!(q(-16384.0).checked_sub(q(16385.0)), Err(CheckedError::Underflow));
+        assert_eq!(q(16384.0).checked_add(q(16384.0)), Err(CheckedError::Overflow));
+        assert_eq!(q(-256.0).checked_mul(q(128.0)), Err(CheckedError::Niche));
+
+        assert_eq!(q(-16384.0).saturating_add(q(-16384.0)), Q16::from_bits(i32::MIN + 1).unwrap());
+        assert_eq!(q(16384.0).saturating_mul(q(16384.0)), Q16::from_bits(i32::MAX).unwrap());
+        assert_eq!(q(1.5).saturating_sub(q(2.25)), q(-0.75));
+
+        type Zero = NonZeroFixedU32<8>;
+        let z = |value: f64| Zero::from_f64(value, RoundingMode::Nearest).unwrap();
+        assert_eq!(z(1.0).checked_sub(z(2.0)), Err(CheckedError::Underflow));
+        assert_eq!(z(1.0).saturating_sub(z(2.0)), Zero::from_bits(1).unwrap());
+        assert_eq!(z(1.0).saturating_sub(z(1.0)), Zero::from_bits(1).unwrap());
+        assert_eq!(z(1.0).checked_sub(z(1.0)), Err(CheckedError::Niche));
+        assert_eq!(z(1.0).saturating_add(z(16777215.0)), Zero::from_bits(u32::MAX).unwrap());
+
+        let mid = NonAnyFixedI32::<16, 0x1_0000>::from_int(3).unwrap();
+        assert_eq!(mid.checked_sub(NonAnyFixedI32::from_int(2).unwrap()), Err(CheckedError::Niche));
+        assert_eq!(mid.saturating_sub(NonAnyFixedI32::from_int(2).unwrap()).to_bits(), 0x1_0001);
+        assert!(q(-1.0) < q(0.5));
+    }
+
+    #[test]
+    fn test_fixed_fmt() {
+        type Q16 = NonMinFixedI32<16>;
+        let q = |value: f64| Q16::from_f64(value, RoundingMode::Nearest).unwrap();
+
+        assert_fmt!("0", "{}", q(0.0));
+        assert_fmt!("1.5", "{}", q(1.5));
+        assert_fmt!("-1.5", "{}", q(-1.5));
+        assert_fmt!("-32767.9999847412109375", "{}", Q16::from_bits(i32::MIN + 1).unwrap());
+        assert_fmt!("0.0000152587890625", "{}", Q16::from_bits(1).unwrap());
+        assert_fmt!("1.50", "{:.2}", q(1.5));
+        assert_fmt!("1.2", "{:.1}", q(1.25));
+        assert_fmt!("1.4", "{:.1}", q(1.375));
+        assert_fmt!("2", "{:.0}", q(2.5));
+        assert_fmt!("4", "{:.0}", q(3.5));
+        assert_fmt!("-1.00", "{:.2}", q(-0.999));
+        assert_fmt!("+1.5", "{:+}", q(1.5));
+        assert_fmt!("  -1.5", "{:>6}", q(-1.5));
+        assert_fmt!("-001.5", "{:06}", q(-1.5));
+        assert_fmt!("NonAnyFixedI32 { value: 1.5, bits: 98304, frac: 16 }", "{:?}", q(1.5));
+
+        assert_fmt!("-0.5", "{}", NonMinFixedI16::<15>::from_bits(-0x4000).unwrap());
+        assert_fmt!("0.5", "{}", NonMaxFixedU64::<64>::from_bits(1 << 63).unwrap());
+        assert_fmt!("18446744073709551614", "{}", NonMaxFixedU64::<0>::from_bits(u64::MAX - 1).unwrap());
+    }
+
+    #[test]
+    fn test_fixed_fmt_long_precision() {
+        extern crate std;
+        use std::string::String;
+
+        let value = NonMinFixedI32::<16>::from_bits(-0x18000).unwrap();
+        let zeros = |count: usize| "0".repeat(count);
+
+        let mut expected = String::from("-1.5");
+        expected.push_str(&zeros(199));
+        assert_fmt!(expected.as_str(), "{:.200}", value);
+        assert_fmt!(std::format!("{}{}", " ".repeat(7), expected).as_str(), "{:>210.200}", value);
+        assert_fmt!(std::format!("{}{}", expected, "*".repeat(7)).as_str(), "{:*<210.200}", value);
+        assert_fmt!(std::format!("{}{}{}", "^".repeat(3), expected, "^".repeat(4)).as_str(), "{:^^210.200}", value);
+        assert_fmt!(std::format!("-{}1.5{}", zeros(7), zeros(199)).as_str(), "{:0210.200}", value);
+        assert_fmt!(expected.as_str(), "{:10.200}", value);
+        assert_fmt!(std::format!("   +1.5{}", zeros(199)).as_str(), "{:>+206.200}", NonMinFixedI32::<16>::from_bits(0x18000).unwrap());
+    }
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+mod atomic {
+    extern crate std;
+
+    use core::sync::atomic::Ordering;
+    use crate::{CheckedError, NonAnyI8, NonAnyU32, NonMaxU32, NonZeroU64};
+    use crate::atomic::{AtomicNonAnyI8, AtomicNonAnyU32, AtomicNonMaxU32, AtomicOptionNonAnyI8, AtomicOptionNonMaxU32, AtomicOptionNonZeroU64, OnceNonAnyU64};
+
+    const _: () = assert!(core::mem::size_of::<AtomicNonMaxU32>() == core::mem::size_of::<u32>());
+    const _: () = assert!(core::mem::size_of::<AtomicOptionNonMaxU32>() == core::mem::size_of::<u32>());
+    const _: () = assert!(core::mem::size_of::<AtomicOptionNonZeroU64>() == core::mem::size_of::<u64>());
+
+    #[test]
+    fn test_atomic() {
+        let value = |value| NonAnyU32::<7>::new(value).unwrap();
+        let mut atomic = AtomicNonAnyU32::new(value(5));
+        assert_eq!(atomic.load(Ordering::Relaxed), value(5));
+        atomic.store(value(6), Ordering::Relaxed);
+        assert_eq!(atomic.swap(value(8), Ordering::Relaxed), value(6));
+        assert_eq!(atomic.compare_exchange(value(6), value(1), Ordering::Relaxed, Ordering::Relaxed), Err(value(8)));
+        assert_eq!(atomic.compare_exchange(value(8), value(1), Ordering::Relaxed, Ordering::Relaxed), Ok(value(8)));
+        assert_eq!(atomic.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |_| None), Err(value(1)));
+        assert_eq!(atomic.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| NonAnyU32::new(current.get() * 3)), Ok(value(1)));
+        *atomic.get_mut() = value(5);
+
+        assert_eq!(atomic.fetch_add(1, Ordering::Relaxed), value(5));
+        assert_eq!(atomic.fetch_add(1, Ordering::Relaxed), value(6));
+        assert_eq!(atomic.load(Ordering::Relaxed), value(8));
+        assert_eq!(atomic.fetch_sub(1, Ordering::Relaxed), value(8));
+        assert_eq!(atomic.into_inner(), value(6));
+
+        let atomic = AtomicNonAnyU32::new(value(u32::MAX));
+        assert_eq!(atomic.fetch_add(2, Ordering::Relaxed), value(u32::MAX));
+        assert_eq!(atomic.load(Ordering::Relaxed), value(1));
+        assert_eq!(atomic.checked_fetch_sub(2, Ordering::Relaxed), Err(CheckedError::Underflow));
+        assert_eq!(atomic.checked_fetch_add(6, Ordering::Relaxed), Err(CheckedError::Niche));
+        assert_eq!(atomic.checked_fetch_add(u32::MAX, Ordering::Relaxed), Err(CheckedError::Overflow));
+        assert_eq!(atomic.checked_fetch_add(5, Ordering::Relaxed), Ok(value(1)));
+        assert_eq!(atomic.load(Ordering::Relaxed), value(6));
+
+        let signed = AtomicNonAnyI8::<-3>::new(NonAnyI8::new(-1).unwrap());
+        assert_eq!(signed.checked_fetch_add(-2, Ordering::Relaxed), Err(CheckedError::Niche));
+        assert_eq!(signed.fetch_add(-2, Ordering::Relaxed).get(), -1);
+        assert_eq!(signed.load(Ordering::Relaxed).get(), -4);
+        assert_eq!(signed.checked_fetch_add(-125, Ordering::Relaxed), Err(CheckedError::Underflow));
+        assert_eq!(signed.checked_fetch_sub(124, Ordering::Relaxed).map(|value| value.get()), Ok(-4));
+        assert_eq!(signed.fetch_sub(1, Ordering::Relaxed).get(), -128);
+        assert_eq!(signed.load(Ordering::Relaxed).get(), 127);
+        assert_eq!(signed.checked_fetch_sub(-1, Ordering::Relaxed), Err(CheckedError::Overflow));
+    }
+
+    #[test]
+    fn test_atomic_option() {
+        let value = |value| NonAnyI8::<-3>::new(value);
+        let mut atomic = AtomicOptionNonAnyI8::<-3>::default();
+        assert_eq!(atomic.load(Ordering::Relaxed), None);
+        assert_eq!(atomic.fetch_add(1, Ordering::Relaxed), None);
+        assert_eq!(atomic.checked_fetch_add(1, Ordering::Relaxed), Ok(None));
+        assert_eq!(atomic.load(Ordering::Relaxed), None);
+        assert_eq!(atomic.compare_exchange(None, value(-4), Ordering::Relaxed, Ordering::Relaxed), Ok(None));
+        assert_eq!(atomic.fetch_add(1, Ordering::Relaxed), value(-4));
+        assert_eq!(atomic.load(Ordering::Relaxed), value(-2));
+        assert_eq!(atomic.checked_fetch_sub(1, Ordering::Relaxed), Err(CheckedError::Niche));
+        assert_eq!(atomic.swap(value(127), Ordering::Relaxed), value(-2));
+        assert_eq!(atomic.checked_fetch_add(1, Ordering::Relaxed), Err(CheckedError::Overflow));
+        assert_eq!(atomic.fetch_add(1, Ordering::Relaxed), value(127));
+        assert_eq!(*atomic.get_mut(), value(-128));
+        assert_eq!(atomic.take(Ordering::Relaxed), value(-128));
+        assert_eq!(atomic.into_inner(), None);
+
+        // The stored bits are the encoded bits, with zero as `None`.
+        let atomic = AtomicOptionNonMaxU32::new(NonMaxU32::new(0));
+        assert_eq!(unsafe { core::mem::transmute::<AtomicOptionNonMaxU32, u32>(atomic) }, u32::MAX);
+        assert_eq!(AtomicOptionNonZeroU64::from(NonZeroU64::new(3).unwrap()).into_inner(), NonZeroU64::new(3));
+    }
+
+    #[test]
+    fn test_once() {
+        let value = |value| crate::NonAnyU64::<42>::new(value).unwrap();
+        let mut once = OnceNonAnyU64::<42>::new();
+        assert_eq!(once.get(), None);
+        assert_eq!(once.get_or_try_init(|| Err(())), Err(()));
+        assert_eq!(once.get(), None);
+        assert_eq!(once.get_or_try_init(|| Ok::<_, ()>(value(0))), Ok(value(0)));
+        assert_eq!(once.get_or_init(|| unreachable!()), value(0));
+        assert_eq!(once.set(value(1)), Err(value(1)));
+        *once.get_mut().unwrap() = value(2);
+        assert_eq!(once.into_inner(), Some(value(2)));
+
+        // The niche marks the cell as uninitialized, so zero is a regular value.
+        let once = OnceNonAnyU64::<42>::default();
+        assert_eq!(once.set(value(0)), Ok(()));
+        assert_eq!(unsafe { core::mem::transmute::<OnceNonAnyU64<42>, u64>(once) }, 42);
+        assert_eq!(std::format!("{:?}", OnceNonAnyU64::<42>::new()), "OnceNonAnyU64(<uninit>)");
+        assert_eq!(std::format!("{:?}", OnceNonAnyU64::from(value(3))), "OnceNonAnyU64(3)");
+    }
+
+    #[test]
+    fn test_once_threads() {
+        let once = OnceNonAnyU64::<0>::new();
+        let values = std::thread::scope(|scope| {
+            let handles: std::vec::Vec<_> = (1..=4)
+                .map(|i| {
+                    let once = &once;
+                    scope.spawn(move || once.get_or_init(|| NonZeroU64::new(i).unwrap()))
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect::<std::vec::Vec<_>>()
+        });
+        assert!(values.iter().all(|value| Some(*value) == once.get()));
+    }
+
+    #[test]
+    fn test_atomic_threads() {
+        let counter = AtomicNonMaxU32::new(NonMaxU32::new(u32::MAX - 1000).unwrap());
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..500 {
+                        counter.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.into_inner().get(), 1000);
+    }
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+mod id {
+    extern crate std;
+
+    use crate::{CheckedError, NonAnyU32, NonMaxU64};
+    use crate::id::{IdAllocatorNonMaxU64, IdAllocatorNonZeroUsize, IdAllocatorU32};
+
+    #[test]
+    fn test_id() {
+        let ids = IdAllocatorNonZeroUsize::<0>::new();
+        assert_eq!(ids.allocate().unwrap().get(), 1);
+        assert_eq!(ids.allocate().unwrap().get(), 2);
+        assert_eq!(ids.free(crate::NonZeroUsize::new(1).unwrap()).map_err(|id| id.get()), Err(1));
+
+        // The niche is skipped, and the allocator is exhausted after the largest value.
+        let value = |value| NonAnyU32::<5>::new(value).unwrap();
+        let ids = IdAllocatorU32::<5>::with_start(value(4));
+        assert_eq!(ids.allocate(), Ok(value(4)));
+        assert_eq!(ids.allocate(), Ok(value(6)));
+
+        let ids = IdAllocatorU32::<5, 2>::with_start(value(u32::MAX - 1));
+        assert_eq!(ids.peek(), Some(value(u32::MAX - 1)));
+        assert_eq!(ids.allocate(), Ok(value(u32::MAX - 1)));
+        assert_eq!(ids.allocate(), Ok(value(u32::MAX)));
+        assert_eq!(ids.peek(), None);
+        assert_eq!(ids.allocate(), Err(CheckedError::Overflow));
+        assert_eq!(ids.allocate(), Err(CheckedError::Overflow));
+
+        assert_eq!(ids.free(value(3)), Ok(()));
+        assert_eq!(ids.free(value(1)), Ok(()));
+        assert_eq!(ids.free(value(2)), Err(value(2)));
+        assert_eq!(ids.allocate(), Ok(value(3)));
+        assert_eq!(ids.allocate(), Ok(value(1)));
+        assert_eq!(ids.allocate(), Err(CheckedError::Overflow));
+
+        let ids = IdAllocatorNonMaxU64::<0>::with_start(NonMaxU64::new(u64::MAX - 1).unwrap());
+        assert_eq!(ids.allocate().unwrap().get(), u64::MAX - 1);
+        assert_eq!(ids.allocate(), Err(CheckedError::Overflow));
+        assert_eq!(std::format!("{:?}", ids), "IdAllocatorU64 { next: None, .. }");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "freed an identifier that has not been allocated")]
+    fn test_id_free_unallocated() {
+        let ids = IdAllocatorU32::<0, 2>::new();
+        ids.allocate().unwrap();
+        let _ = ids.free(NonAnyU32::new(5).unwrap());
+    }
+
+    #[test]
+    fn test_id_threads() {
+        let ids = IdAllocatorU32::<0, 8>::new();
+        let mut allocated: std::vec::Vec<u32> = std::thread::scope(|scope| {
+            let handles: std::vec::Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| {
+                    let mut allocated = std::vec::Vec::new();
+                    for i in 0..1000 {
+                        let id = ids.allocate().unwrap();
+                        if i % 3 == 0 && ids.free(id).is_ok() {
+                            continue;
+                        }
+                        allocated.push(id.get());
+                    }
+                    allocated
+                }))
+                .collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        let len = allocated.len();
+        allocated.sort_unstable();
+        allocated.dedup();
+        assert_eq!(allocated.len(), len);
+        assert!(!allocated.contains(&0));
+    }
+}
+
+mod ffi {
+    use crate::ffi::{NonNegOneI32, SentinelI32, SentinelU8};
+    use crate::{NonAnyU8, NonMaxU8};
+
+    test_size!(NonNegOneI32, i32);
+    const _: () = assert!(core::mem::size_of::<SentinelI32<-1>>() == core::mem::size_of::<i32>());
+
+    #[test]
+    fn test_sentinel() {
+        assert_eq!(SentinelI32::<-1>::new(None).to_raw(), -1);
+        assert_eq!(SentinelI32::<-1>::default(), SentinelI32::NONE);
+        assert_eq!(SentinelI32::new(NonNegOneI32::new(0)).to_raw(), 0);
+        assert_eq!(SentinelI32::<-1>::from_raw(-1).get(), None);
+        assert_eq!(SentinelI32::<-1>::from_raw(-2).get(), NonNegOneI32::new(-2));
+        assert!(SentinelI32::<-1>::from_raw(-1).is_none());
+        assert!(SentinelI32::<-1>::from_raw(7).is_some());
+
+        // The raw value of the sentinel is the XOR of the raw bits of the option with the niche.
+        for value in 0..=u8::MAX {
+            let option = NonAnyU8::<42>::new(value);
+            let bits = unsafe { core::mem::transmute::<Option<NonAnyU8<42>>, u8>(option) };
+            let sentinel = SentinelU8::from(option);
+            assert_eq!(sentinel.to_raw(), value);
+            assert_eq!(sentinel.to_raw(), bits ^ 42);
+            assert_eq!(Option::from(sentinel), option);
+        }
+
+        assert_eq!(SentinelU8::from(NonMaxU8::new(3).unwrap()).to_raw(), 3);
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod index {
+    use alloc::vec::Vec;
+    use crate::index::{Idx, IndexSlice, IndexVec};
+
+    crate::define_index! {
+        struct NodeId(NonMaxU32);
+    }
+
+    crate::define_index! {
+        /// An edge.
+        pub(crate) struct EdgeId(NonMaxUsize);
+    }
+
+    test_size!(NodeId, u32);
+    test_size!(EdgeId, usize);
+
+    #[test]
+    fn test_define_index() {
+        assert_eq!(NodeId::new(3).index(), 3);
+        assert_eq!(NodeId::new(3).get(), 3u32);
+        assert_eq!(NodeId::MAX.get(), u32::MAX - 1);
+        assert_eq!(NodeId::try_from_usize(u32::MAX as usize), None);
+        assert_eq!(EdgeId::try_from_usize(usize::MAX - 1), Some(EdgeId::MAX));
+        assert_eq!(<NodeId as Idx>::new(5), NodeId::new(5));
+        assert_eq!(usize::from(EdgeId::new(9)), 9);
+        assert!(NodeId::new(1) < NodeId::new(2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_define_index_overflow() {
+        NodeId::new(u32::MAX as usize);
+    }
+
+    #[test]
+    fn test_index_vec() {
+        let mut vec = IndexVec::<NodeId, &str>::new();
+        assert!(vec.is_empty());
+        assert_eq!(vec.next_index(), NodeId::new(0));
+
+        let a = vec.push("a");
+        let b = vec.push("b");
+        assert_eq!((a.index(), b.index()), (0, 1));
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec[a], "a");
+        assert_eq!(vec.get(NodeId::new(2)), None);
+
+        vec[b] = "c";
+        vec.swap(a, b);
+        assert_eq!(vec.as_raw(), ["c", "a"]);
+
+        let enumerated: Vec<_> = vec.iter_enumerated().collect();
+        assert_eq!(enumerated, [(a, &"c"), (b, &"a")]);
+        assert_eq!(vec.indices().collect::<Vec<_>>(), [a, b]);
+
+        let slice: &IndexSlice<NodeId, &str> = &vec;
+        assert_eq!(slice[b], "a");
+
+        *vec.ensure_contains_elem(NodeId::new(3), || "-") = "d";
+        assert_eq!(vec.into_raw(), ["c", "a", "-", "d"]);
+
+        let collected: IndexVec<EdgeId, u8> = (0..4).collect();
+        assert_eq!(collected[EdgeId::new(3)], 3);
+        assert_eq!(alloc::format!("{:?}", collected), "[0, 1, 2, 3]");
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod arena {
+    use alloc::vec::Vec;
+    use crate::NonMaxU32;
+    use crate::arena::{Arena, Handle};
+
+    test_size!(Handle, u64);
+
+    #[test]
+    fn test_insert_remove() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        assert_eq!((a.index(), a.generation()), (0, 0));
+        assert_eq!((b.index(), b.generation()), (1, 0));
+        assert_eq!(arena.len(), 2);
+
+        *arena.get_mut(b).unwrap() += 10;
+        assert_eq!(arena[b], 12);
+
+        assert_eq!(arena.remove(a), Some(1));
+        assert_eq!(arena.remove(a), None);
+        assert!(!arena.contains(a));
+        assert_eq!(arena.len(), 1);
+
+        let c = arena.insert(3);
+        assert_eq!((c.index(), c.generation()), (0, 1));
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.get(c), Some(&3));
+        assert_eq!(arena.get(Handle::from_raw_parts(NonMaxU32::new(5).unwrap(), 0)), None);
+        assert_eq!(Handle::from_raw_parts(c.into_raw_parts().0, c.into_raw_parts().1), c);
+    }
+
+    #[test]
+    fn test_free_list() {
+        let mut arena: Arena<usize> = (0..5).collect();
+        let handles: Vec<_> = arena.handles().collect();
+        arena.remove(handles[1]);
+        arena.remove(handles[3]);
+
+        // The most recently vacated slot is reused first.
+        assert_eq!(arena.insert(30).index(), 3);
+        assert_eq!(arena.insert(10).index(), 1);
+        assert_eq!(arena.insert(5).index(), 5);
+        assert_eq!(arena.values().copied().collect::<Vec<_>>(), [0, 10, 2, 30, 4, 5]);
+
+        let h = arena.insert_with(|handle| handle.index());
+        assert_eq!(arena[h], 6);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut arena: Arena<i32> = (0..6).collect();
+        arena.retain(|_, value| *value % 2 == 0);
+        assert_eq!(arena.len(), 3);
+
+        for (_, value) in &mut arena {
+            *value *= 10;
+        }
+        let items: Vec<_> = arena.iter().map(|(handle, value)| (handle.index(), *value)).collect();
+        assert_eq!(items, [(0, 0), (2, 20), (4, 40)]);
+        assert_eq!(arena.iter().len(), 3);
+        assert_eq!(arena.iter().next_back().map(|(_, value)| *value), Some(40));
+        assert_eq!(alloc::format!("{:?}", arena), "{Handle(0v0): 0, Handle(2v0): 20, Handle(4v0): 40}");
+
+        let handles: Vec<_> = arena.handles().collect();
+        let owned: Vec<_> = arena.clone().into_iter().collect();
+        assert_eq!(owned, [(handles[0], 0), (handles[1], 20), (handles[2], 40)]);
+
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_eq!(arena.get(handles[0]), None);
+        assert_eq!(arena.insert(7).generation(), 1);
+    }
+}
+
+#[cfg(feature = "std")]
+mod map {
+    extern crate std;
+
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use std::collections::HashMap;
+    use crate::{NonMaxU8, NonZeroU16, NonZeroU32};
+    use crate::map::{NicheMap, NicheSet};
+
+    fn key(value: u16) -> NonZeroU16 {
+        NonZeroU16::new(value).unwrap()
+    }
+
+    #[test]
+    fn test_map() {
+        let mut map = NicheMap::new();
+        assert_eq!(map.capacity(), 0);
+        assert_eq!(map.get(key(1)), None);
+        assert_eq!(map.remove(key(1)), None);
+
+        assert_eq!(map.insert(key(1), "a"), None);
+        assert_eq!(map.insert(key(2), "b"), None);
+        assert_eq!(map.insert(key(1), "c"), Some("a"));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[key(1)], "c");
+        assert!(map.contains_key(key(2)));
+        assert!(!map.contains_key(key(3)));
+
+        *map.get_or_insert_with(key(3), || "d") = "e";
+        assert_eq!(*map.get_or_insert_with(key(3), || "f"), "e");
+
+        let mut items: Vec<_> = map.iter().map(|(key, value)| (key.get(), *value)).collect();
+        items.sort_unstable();
+        assert_eq!(items, [(1, "c"), (2, "b"), (3, "e")]);
+        assert_eq!(map.keys().len(), 3);
+
+        let clone = map.clone();
+        assert_eq!(map.remove(key(2)), Some("b"));
+        assert_ne!(clone, map);
+        assert_eq!(alloc::format!("{:?}", NicheMap::<NonZeroU16, ()>::new()), "{}");
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.capacity(), 6);
+        map.shrink_to_fit();
+        assert_eq!(map.capacity(), 0);
+    }
+
+    #[test]
+    fn test_map_against_hash_map() {
+        let mut map = NicheMap::new();
+        let mut expected = HashMap::new();
+
+        // A small key space forces long clusters, which exercises the backward-shift deletion.
+        let mut state = 0x2545_f491_u32;
+        for step in 0..20_000u32 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let value = (state % 200 + 1) as u16;
+            match state % 3 {
+                0 => assert_eq!(map.remove(key(value)), expected.remove(&value)),
+                _ => assert_eq!(map.insert(key(value), step), expected.insert(value, step)),
+            }
+            assert_eq!(map.len(), expected.len());
+            if step % 1000 == 0 {
+                map.retain(|key, value| (key.get() as u32 + *value) % 4 != 0);
+                expected.retain(|key, value| (*key as u32 + *value) % 4 != 0);
+            }
+        }
+
+        for value in 1..=200 {
+            assert_eq!(map.get(key(value)), expected.get(&value));
+        }
+        let mut items: Vec<_> = map.into_iter().map(|(key, value)| (key.get(), value)).collect();
+        let mut expected: Vec<_> = expected.into_iter().collect();
+        items.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn test_map_drop() {
+        let value = Rc::new(());
+        let mut map: NicheMap<NonZeroU32, _> = (1..=100).map(|key| (NonZeroU32::new(key).unwrap(), value.clone())).collect();
+        assert_eq!(Rc::strong_count(&value), 101);
+
+        map.retain(|key, _| key.get() % 2 == 0);
+        assert_eq!(Rc::strong_count(&value), 51);
+
+        let mut iter = map.clone().into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&value), 100);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&value), 51);
+
+        drop(map);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set: NicheSet<NonMaxU8> = (0..=254).filter_map(NonMaxU8::new).collect();
+        assert_eq!(set.len(), 255);
+        assert!(set.capacity() >= 255);
+        assert!(!set.insert(NonMaxU8::new(7).unwrap()));
+
+        set.retain(|key| key.get() < 3);
+        assert!(set.remove(NonMaxU8::new(1).unwrap()));
+        assert!(!set.contains(NonMaxU8::new(1).unwrap()));
+
+        let mut keys: Vec<_> = set.iter().map(NonMaxU8::get).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, [0, 2]);
+        assert_eq!(set.into_iter().len(), 2);
+    }
+}
+
+mod hash {
+    extern crate std;
+
+    use core::hash::{BuildHasher, Hash, Hasher};
+    use std::collections::hash_map::RandomState;
+    use crate::{NonAnyI16, NonAnyU32, NonMaxU32, NonMinI16, NonZeroU32Be, NonZeroU32Le, offset};
+
+    fn hash<T: Hash>(state: &RandomState, value: T) -> u64 {
+        let mut hasher = state.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_hash() {
+        let state = RandomState::new();
+        for value in [0u32, 1, 5, 7, u32::MAX - 1] {
+            let expected = hash(&state, value);
+            assert_eq!(hash(&state, NonAnyU32::<9>::new(value).unwrap()), expected);
+            assert_eq!(hash(&state, NonMaxU32::new(value).unwrap()), expected);
+            assert_eq!(hash(&state, offset::NonAnyU32::<9>::new(value).unwrap()), expected);
+            assert_eq!(hash(&state, NonZeroU32Be::new(value + 1).unwrap()), hash(&state, value + 1));
+            assert_eq!(hash(&state, NonZeroU32Le::new(value + 1).unwrap()), hash(&state, value + 1));
+        }
+
+        for value in [i16::MIN + 1, -1, 0, 1, i16::MAX] {
+            assert_eq!(hash(&state, NonMinI16::new(value).unwrap()), hash(&state, value));
+            assert_eq!(hash(&state, NonAnyI16::<-5>::new(value).unwrap()), hash(&state, value));
+        }
+    }
+
+    #[cfg(feature = "equivalent")]
+    #[test]
+    fn test_equivalent() {
+        use crate::equivalent::ByValue;
+
+        let mut map = hashbrown::HashMap::new();
+        map.insert(NonMaxU32::new(7).unwrap(), 1);
+        map.insert(NonMaxU32::new(0).unwrap(), 2);
+        assert_eq!(map.get(&7u32), Some(&1));
+        assert_eq!(map.get(&0u32), Some(&2));
+        assert_eq!(map.get(&u32::MAX), None);
+        assert_eq!(map.get(&ByValue(NonAnyU32::<0>::new(7).unwrap())), Some(&1));
+        assert_eq!(map.get(&ByValue(NonAnyU32::<7>::new(0).unwrap())), Some(&2));
+        assert_eq!(map.get(&ByValue(NonMaxU32::new(3).unwrap())), None);
+
+        let mut set = indexmap::IndexSet::new();
+        set.insert(NonMinI16::new(-3).unwrap());
+        assert!(set.contains(&-3i16));
+        assert!(set.contains(&ByValue(NonAnyI16::<0>::new(-3).unwrap())));
+        assert!(!set.contains(&i16::MIN));
+    }
+}
+
+mod cmp {
+    use core::cmp::Ordering;
+    use core::convert::TryInto;
+    use crate::{NonAnyI8, NonAnyI128, NonAnyU16, NonAnyU32, NonAnyU128, NonMaxI64, NonMaxU32, NonMinI8, NonZeroUsize, Value, offset};
+
+    fn contains<T: Value>(values: &[NonMaxU32], value: T) -> bool {
+        values.iter().any(|v| v.value_eq(value))
+    }
+
+    #[test]
+    fn test_value_bound() {
+        let values = [NonMaxU32::new(1).unwrap(), NonMaxU32::new(300).unwrap()];
+        assert!(contains(&values, 300u16));
+        assert!(contains(&values, NonMinI8::new(1).unwrap()));
+        assert!(!contains(&values, -1i64));
+    }
+
+    #[test]
+    fn test_const_pattern() {
+        const ONE: NonMaxU32 = NonMaxU32::new_const::<1>();
+
+        match NonMaxU32::new(1).unwrap() {
+            ONE => {}
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_eq_inference() {
+        let value = NonMaxU32::new(7).unwrap();
+        let other: u64 = 7;
+        assert!(value == other.try_into().unwrap());
+    }
+
+    #[test]
+    fn test_cmp_primitive() {
+        let value = NonMaxU32::new(7).unwrap();
+        assert!(value.value_eq(7u32));
+        assert!(!value.value_eq(8u32));
+        assert!(value.value_eq(7i8));
+        assert_eq!(value.value_cmp(8u32), Ordering::Less);
+        assert_eq!(value.value_cmp(6u32), Ordering::Greater);
+
+        let value = NonMinI8::new(-3).unwrap();
+        assert!(value.value_eq(-3i8));
+        assert_eq!(value.value_cmp(0u64), Ordering::Less);
+        assert_eq!(value.value_cmp(i8::MIN), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_cmp_niche() {
+        assert!(NonAnyU32::<5>::new(7).unwrap().value_eq(NonAnyU32::<9>::new(7).unwrap()));
+        assert!(!NonAnyU32::<5>::new(7).unwrap().value_eq(NonMaxU32::new(8).unwrap()));
+        assert_eq!(NonAnyU32::<5>::new(7).unwrap().value_cmp(NonMaxU32::new(8).unwrap()), Ordering::Less);
+        assert!(offset::NonAnyU32::<5>::new(7).unwrap().value_eq(NonMaxU32::new(7).unwrap()));
+
+        for a in i8::MIN..=i8::MAX {
+            for b in i8::MIN..=i8::MAX {
+                if let (Some(x), Some(y)) = (NonAnyI8::<3>::new(a), NonAnyI8::<-3>::new(b)) {
+                    assert_eq!(x.value_eq(y), a == b);
+                    assert_eq!(x.value_cmp(y), a.cmp(&b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cmp_width() {
+        assert!(NonAnyU16::<0>::new(300).unwrap().value_eq(NonMaxI64::new(300).unwrap()));
+        assert!(!NonAnyU16::<0>::new(300).unwrap().value_eq(NonAnyI8::<0>::new(44).unwrap()));
+        assert_eq!(NonMinI8::new(-1).unwrap().value_cmp(NonZeroUsize::new(1).unwrap()), Ordering::Less);
+        assert_eq!(NonZeroUsize::new(1).unwrap().value_cmp(NonMinI8::new(-1).unwrap()), Ordering::Greater);
+        assert_eq!(NonMaxI64::new(-1).unwrap().value_cmp(u128::MAX), Ordering::Less);
+
+        let min = NonAnyI128::<0>::new(i128::MIN).unwrap();
+        let max = NonAnyU128::<0>::new(u128::MAX).unwrap();
+        assert_eq!(min.value_cmp(max), Ordering::Less);
+        assert_eq!(max.value_cmp(min), Ordering::Greater);
+        assert!(NonAnyI128::<0>::new(i128::MAX).unwrap().value_eq(i128::MAX as u128));
+        assert_eq!(NonAnyI128::<0>::new(i128::MAX).unwrap().value_cmp(i128::MAX as u128 + 1), Ordering::Less);
+
+        for a in i8::MIN..=i8::MAX {
+            for b in 0..=u16::MAX {
+                if let (Some(x), Some(y)) = (NonMinI8::new(a), NonAnyU16::<300>::new(b)) {
+                    assert_eq!(x.value_eq(y), i32::from(a) == i32::from(b));
+                    assert_eq!(x.value_cmp(y), i32::from(a).cmp(&i32::from(b)));
+                    assert_eq!(y.value_cmp(x), i32::from(b).cmp(&i32::from(a)));
+                }
+            }
+        }
+    }
+}
+
+mod varint {
+    use crate::varint::{DecodeError, Varint};
+
+    #[test]
+    fn test_varint_rank() {
+        let mut buffer = [0; 19];
+
+        assert_eq!(crate::NonMaxU64::new(0).encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 1);
+        assert_eq!(crate::NonZeroU64::new(1).encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 1);
+        assert_eq!(crate::NonZeroI64::new(-1).encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 1);
+        assert_eq!(crate::NonMinI64::new(0).encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 1);
+        assert_eq!(crate::NonMinI64::new(-1).encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 2);
+
+        assert_eq!(crate::NonMaxU64::new(0).unwrap().encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 0);
+        assert_eq!(crate::NonZeroU64::new(1).unwrap().encode(&mut buffer), Some(1));
+        assert_eq!(buffer[0], 0);
+    }
+
+    macro_rules! test_varint {
+        ($nonany:ident, $int:ident) => {
+            {
+                type NonAny = crate::$nonany<7>;
+
+                let mut buffer = [0; <Option<NonAny> as Varint>::MAX_LEN];
+                assert_eq!(None::<NonAny>.encode(&mut buffer), Some(1));
+                assert_eq!(buffer[0], 0);
+                assert_eq!(<Option<NonAny>>::decode(&buffer[..1]), Ok((None, 1)));
+
+                for value in [$int::MIN, $int::MIN + 1, 0, 6, 8, $int::MAX - 1, $int::MAX] {
+                    let non = NonAny::new(value).unwrap();
+
+                    let len = Some(non).encode(&mut buffer).unwrap();
+                    assert_eq!(<Option<NonAny>>::decode(&buffer[..len]), Ok((Some(non), len)));
+                    assert_eq!(<Option<NonAny>>::decode(&buffer[..len - 1]), Err(DecodeError::Truncated));
+                    assert_eq!(Some(non).encode(&mut buffer[..len - 1]), None);
+
+                    let len = non.encode(&mut buffer).unwrap();
+                    assert_eq!(NonAny::decode(&buffer[..len]), Ok((non, len)));
+                }
+
+                let mut overflow = [0xFF; <Option<NonAny> as Varint>::MAX_LEN + 1];
+                overflow[<Option<NonAny> as Varint>::MAX_LEN] = 0;
+                assert_eq!(<Option<NonAny>>::decode(&overflow), Err(DecodeError::Overflow));
+                assert_eq!(NonAny::decode(&overflow), Err(DecodeError::Overflow));
+            }
+        };
+    }
+
+    macro_rules! test_varint_ranks {
+        ($nonany:ident, $niche:expr) => {
+            {
+                type NonAny = crate::$nonany<{ $niche }>;
+
+                // Every rank that fits in two bytes decodes, and encodes back to the same bytes.
+                for rank in 0..=u8::MAX {
+                    let varint = if rank < 0x80 { [rank, 0] } else { [rank, 1] };
+                    let mut encoded = [0; 2];
+
+                    let (value, len) = <Option<NonAny>>::decode(&varint).unwrap();
+                    assert_eq!(value.is_none(), rank == 0);
+                    assert_eq!(value.encode(&mut encoded), Some(len));
+                    assert_eq!(encoded[..len], varint[..len]);
+
+                    match NonAny::decode(&varint) {
+                        Ok((value, len)) => {
+                            assert_eq!(value.encode(&mut encoded), Some(len));
+                            assert_eq!(encoded[..len], varint[..len]);
+                        },
+                        Err(err) => assert_eq!((rank, err), (u8::MAX, DecodeError::Overflow))
+                    }
+                }
+
+                // The values next to the niche round-trip on both sides of it.
+                for value in [$niche.wrapping_sub(1), $niche.wrapping_add(1)] {
+                    let non = NonAny::new(value).unwrap();
+                    let mut buffer = [0; 2];
+                    let len = Some(non).encode(&mut buffer).unwrap();
+                    assert_eq!(<Option<NonAny>>::decode(&buffer[..len]), Ok((Some(non), len)));
+                    let len = non.encode(&mut buffer).unwrap();
+                    assert_eq!(NonAny::decode(&buffer[..len]), Ok((non, len)));
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_varint_ranks() {
+        test_varint_ranks!(NonAnyI8, i8::MIN);
+        test_varint_ranks!(NonAnyI8, -1i8);
+        test_varint_ranks!(NonAnyI8, 0i8);
+        test_varint_ranks!(NonAnyI8, 7i8);
+        test_varint_ranks!(NonAnyI8, i8::MAX);
+
+        test_varint_ranks!(NonAnyU8, 0u8);
+        test_varint_ranks!(NonAnyU8, 7u8);
+        test_varint_ranks!(NonAnyU8, 0x80u8);
+        test_varint_ranks!(NonAnyU8, u8::MAX);
+    }
+
+    #[test]
+    fn test_varint() {
+        test_varint!(NonAnyI8, i8);
+        test_varint!(NonAnyI16, i16);
+        test_varint!(NonAnyI32, i32);
+        test_varint!(NonAnyI64, i64);
+        test_varint!(NonAnyI128, i128);
+        test_varint!(NonAnyIsize, isize);
+
+        test_varint!(NonAnyU8, u8);
+        test_varint!(NonAnyU16, u16);
+        test_varint!(NonAnyU32, u32);
+        test_varint!(NonAnyU64, u64);
+        test_varint!(NonAnyU128, u128);
+        test_varint!(NonAnyUsize, usize);
+    }
+}
+
+mod derive {
+    use nonany_derive::NonAnyRepr;
+
+    #[derive(Clone, Copy, Debug, PartialEq, NonAnyRepr)]
+    #[nonany(crate = "crate")]
+    #[repr(u8)]
+    enum ZeroFree {
+        A = 1,
+        B,
+        C = 10,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, NonAnyRepr)]
+    #[nonany(crate = "crate")]
+    #[repr(u8)]
+    enum MaxFree {
+        A,
+        B,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, NonAnyRepr)]
+    #[nonany(crate = "crate")]
+    #[repr(i16)]
+    enum MinFree {
+        A = -1,
+        B,
+        C = 32767,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, NonAnyRepr)]
+    #[nonany(crate = "crate", niche = 7)]
+    #[repr(u32)]
+    enum Explicit {
+        A = 1 << 4,
+        B = 1 << 5,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, NonAnyRepr)]
+    #[nonany(crate = "crate")]
+    #[repr(usize)]
+    enum PointerSized {
+        A,
+        B,
+        C = 3,
+    }
+
+    #[test]
+    fn test_derive_niche() {
+        assert_eq!(<ZeroFree as crate::NonAnyRepr>::NonAny::NICHE, 0);
+        assert_eq!(<MaxFree as crate::NonAnyRepr>::NonAny::NICHE, u8::MAX);
+        assert_eq!(<MinFree as crate::NonAnyRepr>::NonAny::NICHE, i16::MIN);
+        assert_eq!(<Explicit as crate::NonAnyRepr>::NonAny::NICHE, 7);
+        assert_eq!(<PointerSized as crate::NonAnyRepr>::NonAny::NICHE, 2);
+
+        assert_eq!(core::mem::size_of::<Option<<MinFree as crate::NonAnyRepr>::NonAny>>(), 2);
+    }
+
+    #[test]
+    fn test_derive_roundtrip() {
+        for value in [ZeroFree::A, ZeroFree::B, ZeroFree::C] {
+            let non: crate::NonZeroU8 = value.into();
+            assert_eq!(non.get(), value as u8);
+            assert_eq!(ZeroFree::try_from(non), Ok(value));
+        }
+
+        for value in [MinFree::A, MinFree::B, MinFree::C] {
+            let non: crate::NonMinI16 = value.into();
+            assert_eq!(non.get(), value as i16);
+            assert_eq!(MinFree::try_from(non), Ok(value));
+        }
+
+        let unknown = crate::NonAnyU32::<7>::new(3).unwrap();
+        assert_eq!(Explicit::try_from(unknown), Err(unknown));
+        assert_eq!(Explicit::try_from(crate::NonAnyU32::<7>::new(32).unwrap()), Ok(Explicit::B));
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    extern crate std;
+    use std::string::ToString;
+    use ::serde::de::{Deserialize, IntoDeserializer};
+    use ::serde::de::value::{Error, StrDeserializer};
+
+    macro_rules! test_serde {
+        ($nonany:ident, $int:ident) => {
+            {
+                type NonAny = crate::$nonany<7>;
+
+                for value in [$int::MIN, $int::MIN + 1, 0, 1, $int::MAX - 1, $int::MAX] {
+                    let non = NonAny::new(value).unwrap();
+                    let json = serde_json::to_string(&non).unwrap();
+                    assert_eq!(json, serde_json::to_string(&value).unwrap());
+                    assert_eq!(serde_json::from_str::<NonAny>(&json).unwrap(), non);
+                    assert_eq!(serde_json::from_str::<Option<NonAny>>(&json).unwrap(), Some(non));
+                }
+
+                let err = serde_json::from_str::<NonAny>("7").unwrap_err();
+                assert_eq!(err.to_string(), concat!("invalid value: integer `7`, expected a ", stringify!($int), " other than the niche 7 at line 1 column 1"));
+                assert_eq!(serde_json::from_str::<Option<NonAny>>("null").unwrap(), None);
+                assert!(serde_json::from_str::<NonAny>("1.5").is_err());
+
+                let deserializer: StrDeserializer<'_, Error> = "1".into_deserializer();
+                assert_eq!(NonAny::deserialize(deserializer).unwrap(), NonAny::new(1).unwrap());
+                let deserializer: StrDeserializer<'_, Error> = "7".into_deserializer();
+                assert_eq!(NonAny::deserialize(deserializer).unwrap_err().to_string(), concat!("invalid value: string \"7\", expected a ", stringify!($int), " other than the niche 7"));
+            }
+        };
+    }
+
+    #[test]
+    fn test_serde() {
+        test_serde!(NonAnyI8, i8);
+        test_serde!(NonAnyI16, i16);
+        test_serde!(NonAnyI32, i32);
+        test_serde!(NonAnyI64, i64);
+        test_serde!(NonAnyI128, i128);
+        test_serde!(NonAnyIsize, isize);
+
+        test_serde!(NonAnyU8, u8);
+        test_serde!(NonAnyU16, u16);
+        test_serde!(NonAnyU32, u32);
+        test_serde!(NonAnyU64, u64);
+        test_serde!(NonAnyU128, u128);
+        test_serde!(NonAnyUsize, usize);
+    }
+
+    #[test]
+    fn test_serde_range() {
+        assert_eq!(serde_json::from_str::<crate::NonMaxU8>("256").unwrap_err().to_string(), "invalid value: integer `256`, expected a u8 other than the niche 255 at line 1 column 3");
+        assert_eq!(serde_json::from_str::<crate::NonMaxU8>("-1").unwrap_err().to_string(), "invalid value: integer `-1`, expected a u8 other than the niche 255 at line 1 column 2");
+        assert_eq!(serde_json::from_str::<crate::NonMinI128>("-170141183460469231731687303715884105728").unwrap_err().to_string(), "invalid value: 128-bit integer, expected a i128 other than the niche -170141183460469231731687303715884105728 at line 1 column 40");
+    }
+
+    #[test]
+    fn test_serde_string() {
+        assert!(serde_json::from_str::<crate::NonMaxU128>("\"1\"").is_err());
+
+        #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+        struct Wrapper {
+            #[serde(with = "crate::serde::string")]
+            value: crate::NonMaxU128,
+        }
+
+        let wrapper = Wrapper { value: crate::NonMaxU128::new(u128::MAX - 1).unwrap() };
+        let json = serde_json::to_string(&wrapper).unwrap();
+        assert_eq!(json, "{\"value\":\"340282366920938463463374607431768211454\"}");
+        assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), wrapper);
+        assert_eq!(serde_json::from_str::<Wrapper>("{\"value\":\"340282366920938463463374607431768211455\"}").unwrap_err().to_string(), "number is equal to the niche of the target type at line 1 column 50");
+    }
+
+    #[test]
+    fn test_serde_sentinel() {
+        #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+        struct Sentinel {
+            #[serde(with = "crate::serde::sentinel")]
+            signed: Option<crate::NonAnyI32<-1>>,
+            #[serde(with = "crate::serde::sentinel")]
+            unsigned: Option<crate::NonMaxU32>,
+        }
+
+        #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+        struct NullOrSentinel {
+            #[serde(with = "crate::serde::null_or_sentinel")]
+            value: Option<crate::NonMaxU128>,
+        }
+
+        let none = Sentinel { signed: None, unsigned: None };
+        assert_eq!(serde_json::to_string(&none).unwrap(), "{\"signed\":-1,\"unsigned\":4294967295}");
+        assert_eq!(serde_json::from_str::<Sentinel>("{\"signed\":-1,\"unsigned\":4294967295}").unwrap(), none);
+
+        let some = Sentinel { signed: crate::NonAnyI32::new(-2), unsigned: crate::NonMaxU32::new(0) };
+        assert_eq!(serde_json::to_string(&some).unwrap(), "{\"signed\":-2,\"unsigned\":0}");
+        assert_eq!(serde_json::from_str::<Sentinel>("{\"signed\":-2,\"unsigned\":0}").unwrap(), some);
+
+        assert!(serde_json::from_str::<Sentinel>("{\"signed\":null,\"unsigned\":0}").is_err());
+        assert!(serde_json::from_str::<Sentinel>("{\"signed\":-1,\"unsigned\":4294967296}").is_err());
+
+        assert_eq!(serde_json::from_str::<NullOrSentinel>("{\"value\":null}").unwrap(), NullOrSentinel { value: None });
+        assert_eq!(serde_json::from_str::<NullOrSentinel>("{\"value\":340282366920938463463374607431768211455}").unwrap(), NullOrSentinel { value: None });
+        assert_eq!(serde_json::from_str::<NullOrSentinel>("{\"value\":5}").unwrap(), NullOrSentinel { value: crate::NonMaxU128::new(5) });
+        assert_eq!(serde_json::to_string(&NullOrSentinel { value: None }).unwrap(), "{\"value\":340282366920938463463374607431768211455}");
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck {
+    use ::bytemuck::checked::CheckedCastError;
+    use ::bytemuck::TransparentWrapper;
+
+    macro_rules! test_bytemuck {
+        ($nonany:ident, $nonzero:ident, $int:ident) => {
+            {
+                type NonAny = crate::$nonany<7>;
+
+                let raw: [$int; 4] = [0, 7 ^ 1, 7 ^ 5, 7 ^ $int::MAX];
+                let values: &[Option<NonAny>] = ::bytemuck::cast_slice(&raw);
+                assert_eq!(values, [None, NonAny::new(1), NonAny::new(5), NonAny::new($int::MAX)]);
+                assert_eq!(::bytemuck::cast_slice::<Option<NonAny>, $int>(values), raw);
+                assert_eq!(<Option<NonAny> as ::bytemuck::Zeroable>::zeroed(), None);
+
+                assert_eq!(::bytemuck::checked::try_cast::<$int, NonAny>(7 ^ 5), Ok(NonAny::new(5).unwrap()));
+                assert_eq!(::bytemuck::checked::try_cast::<$int, NonAny>(0), Err(CheckedCastError::InvalidBitPattern));
+                assert_eq!(::bytemuck::checked::try_cast_slice::<$int, NonAny>(&raw[1..]).unwrap(), [NonAny::new(1).unwrap(), NonAny::new(5).unwrap(), NonAny::new($int::MAX).unwrap()]);
+                assert_eq!(::bytemuck::checked::try_cast_slice::<$int, NonAny>(&raw), Err(CheckedCastError::InvalidBitPattern));
+                assert_eq!(::bytemuck::cast::<NonAny, $int>(NonAny::new(5).unwrap()), 7 ^ 5);
+
+                let nonzero = core::num::$nonzero::new(7 ^ 5).unwrap();
+                assert_eq!(NonAny::wrap(nonzero), NonAny::new(5).unwrap());
+                assert_eq!(NonAny::peel(NonAny::new(5).unwrap()), nonzero);
+            }
+        };
+    }
+
+    #[test]
+    fn test_bytemuck() {
+        test_bytemuck!(NonAnyI8, NonZeroI8, i8);
+        test_bytemuck!(NonAnyI16, NonZeroI16, i16);
+        test_bytemuck!(NonAnyI32, NonZeroI32, i32);
+        test_bytemuck!(NonAnyI64, NonZeroI64, i64);
+        test_bytemuck!(NonAnyI128, NonZeroI128, i128);
+        test_bytemuck!(NonAnyIsize, NonZeroIsize, isize);
+
+        test_bytemuck!(NonAnyU8, NonZeroU8, u8);
+        test_bytemuck!(NonAnyU16, NonZeroU16, u16);
+        test_bytemuck!(NonAnyU32, NonZeroU32, u32);
+        test_bytemuck!(NonAnyU64, NonZeroU64, u64);
+        test_bytemuck!(NonAnyU128, NonZeroU128, u128);
+        test_bytemuck!(NonAnyUsize, NonZeroUsize, usize);
+    }
+}
+
+#[cfg(feature = "zerocopy")]
+mod zerocopy {
+    use ::zerocopy::{FromBytes, IntoBytes, KnownLayout, Immutable, TryFromBytes, Unaligned};
+
+    const _: () = assert!(impls::impls!(crate::NonZeroU32Be: Unaligned & TryFromBytes & IntoBytes));
+
+    #[derive(TryFromBytes, IntoBytes, KnownLayout, Immutable)]
+    #[repr(C)]
+    struct Header {
+        stream: crate::NonZeroU32Be,
+        length: crate::NonZeroU16Le,
+        flags: u8,
+        kind: crate::NonZeroU8,
+    }
+
+    #[derive(FromBytes, IntoBytes, KnownLayout, Immutable)]
+    #[repr(C)]
+    struct Raw(u64);
+
+    #[test]
+    fn test_zerocopy() {
+        let bytes = [0, 0, 1, 2, 3, 0, 4, 5];
+        let header = Header::try_ref_from_bytes(&bytes).unwrap();
+        assert_eq!(header.stream.get(), 0x0102);
+        assert_eq!(header.length.get(), 3);
+        assert_eq!(header.flags, 4);
+        assert_eq!(header.kind.get(), 5);
+        assert_eq!(header.as_bytes(), bytes);
+
+        assert!(Header::try_ref_from_bytes(&[0, 0, 0, 0, 3, 0, 4, 5]).is_err());
+        assert!(Header::try_ref_from_bytes(&[0, 0, 1, 2, 0, 0, 4, 5]).is_err());
+        assert!(Header::try_ref_from_bytes(&[0, 0, 1, 2, 3, 0, 4, 0]).is_err());
+
+        let value = crate::NonMaxU64::new(5).unwrap();
+        assert_eq!(value.as_bytes(), (!5u64).to_ne_bytes());
+        assert_eq!(crate::NonMaxU64::try_read_from_bytes(&(!5u64).to_ne_bytes()), Ok(value));
+        assert!(crate::NonMaxU64::try_read_from_bytes(&[0; 8]).is_err());
+        assert_eq!(Raw::read_from_bytes(value.as_bytes()).unwrap().0, !5);
+    }
+}
+
+#[cfg(feature = "abi_stable")]
+mod abi_stable {
+    use abi_stable::{abi_stability::check_layout_compatibility, StableAbi};
+    use crate::{NonAnyI32, NonMaxU32, NonZeroU32, NonZeroU32Be, ffi::SentinelI32, fixed::NonMinFixedI32, offset};
+
+    #[derive(StableAbi)]
+    #[repr(C)]
+    struct Plugin {
+        id: Option<NonMaxU32>,
+        flags: NonZeroU32Be,
+        scale: Option<NonMinFixedI32<16>>,
+        fd: SentinelI32<-1>,
+    }
+
+    fn compatible<A: StableAbi, B: StableAbi>() -> bool {
+        check_layout_compatibility(A::LAYOUT, B::LAYOUT).is_ok()
+    }
+
+    #[test]
+    fn test_abi_stable() {
+        assert!(compatible::<Plugin, Plugin>());
+        assert!(compatible::<NonMaxU32, NonMaxU32>());
+        assert!(compatible::<Option<NonAnyI32<5>>, Option<NonAnyI32<5>>>());
+        assert!(!compatible::<NonMaxU32, NonZeroU32>());
+        assert!(!compatible::<NonAnyI32<5>, NonAnyI32<6>>());
+        assert!(!compatible::<NonMinFixedI32<16>, NonMinFixedI32<8>>());
+        assert!(!compatible::<SentinelI32<-1>, SentinelI32<0>>());
+        assert!(!compatible::<NonMaxU32, offset::NonMaxU32>());
+    }
+}
+
+#[cfg(feature = "nightly")]
+mod nightly {
+    macro_rules! test_nightly {
+        ($name:ident, $int:ident) => {
+            {
+                type Nightly = crate::nightly::$name;
+
+                const _: () = assert!(core::mem::size_of::<Option<Nightly>>() == core::mem::size_of::<$int>());
+                assert_eq!(Nightly::new(Nightly::NICHE), None);
+                assert_eq!(Nightly::try_from(Nightly::NICHE), Err(crate::CheckedError::Niche));
+                assert_eq!(unsafe { core::mem::transmute::<$int, Option<Nightly>>(Nightly::NICHE) }, None);
+
+                for value in [$int::MIN, $int::MIN.wrapping_add(1), 0, 1, $int::MAX.wrapping_sub(1), $int::MAX] {
+                    if value == Nightly::NICHE {
+                        continue;
+                    }
+
+                    let nightly = Nightly::new(value).unwrap();
+                    assert_eq!(nightly.get(), value);
+                    assert_eq!(unsafe { core::mem::transmute::<Nightly, $int>(nightly) }, value);
+                    assert_eq!(unsafe { core::mem::transmute::<$int, Option<Nightly>>(value) }, Some(nightly));
+                    assert_eq!(Nightly::from(crate::$name::new(value).unwrap()), nightly);
+                    assert_eq!(crate::$name::from(nightly), crate::$name::new(value).unwrap());
+                    assert_eq!(Nightly::try_from(value), Ok(nightly));
+                    assert_eq!(Nightly::from_str_radix(nightly.write_decimal(&mut [0; Nightly::MAX_DECIMAL_LEN]), 10), Ok(nightly));
+                    assert!(nightly.value_eq(value));
+                    assert!(nightly.value_eq(nightly.to_xor()));
+                    assert_eq!(nightly.value_cmp(value), core::cmp::Ordering::Equal);
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_nightly() {
+        test_nightly!(NonMinI8, i8);
+        test_nightly!(NonMinI16, i16);
+        test_nightly!(NonMinI32, i32);
+        test_nightly!(NonMinI64, i64);
+        test_nightly!(NonMinI128, i128);
+        test_nightly!(NonMinIsize, isize);
+
+        test_nightly!(NonMaxI8, i8);
+        test_nightly!(NonMaxI16, i16);
+        test_nightly!(NonMaxI32, i32);
+        test_nightly!(NonMaxI64, i64);
+        test_nightly!(NonMaxI128, i128);
+        test_nightly!(NonMaxIsize, isize);
+
+        test_nightly!(NonMinU8, u8);
+        test_nightly!(NonMinU16, u16);
+        test_nightly!(NonMinU32, u32);
+        test_nightly!(NonMinU64, u64);
+        test_nightly!(NonMinU128, u128);
+        test_nightly!(NonMinUsize, usize);
+
+        test_nightly!(NonMaxU8, u8);
+        test_nightly!(NonMaxU16, u16);
+        test_nightly!(NonMaxU32, u32);
+        test_nightly!(NonMaxU64, u64);
+        test_nightly!(NonMaxU128, u128);
+        test_nightly!(NonMaxUsize, usize);
+    }
+
+    #[test]
+    fn test_nightly_conversions() {
+        use crate::nightly::{NonMaxU32, NonMinI8};
+
+        const ONE: NonMaxU32 = NonMaxU32::new_const::<1>();
+
+        assert!(matches!(NonMaxU32::new(1), Some(ONE)));
+        assert!(!matches!(NonMaxU32::new(2), Some(ONE)));
+
+        assert_eq!(NonMaxU32::try_from(crate::NonAnyU8::<7>::new(1).unwrap()), Ok(ONE));
+        assert_eq!(NonMaxU32::try_from(crate::NonAnyI64::<7>::new(-1).unwrap()), Err(crate::CheckedError::Underflow));
+        assert_eq!(NonMaxU32::try_from(core::num::NonZeroU64::new(1).unwrap()), Ok(ONE));
+        assert_eq!(NonMinI8::try_from(core::num::NonZeroI16::new(-128).unwrap()), Err(crate::CheckedError::Niche));
+
+        assert!(ONE.value_eq(crate::NonAnyI128::<5>::new(1).unwrap()));
+        assert_eq!(ONE.value_cmp(2i8), core::cmp::Ordering::Less);
+        assert_eq!(std::format!("{:x} {:#X}", NonMaxU32::new(255).unwrap(), NonMaxU32::new(255).unwrap()), "ff 0xFF");
+    }
+
+    #[test]
+    fn test_nightly_varint() {
+        use crate::varint::Varint;
+        use crate::nightly::NonMaxU32;
+
+        let mut buffer = [0; <Option<NonMaxU32> as Varint>::MAX_LEN];
+
+        for value in [NonMaxU32::new(0), NonMaxU32::new(300), NonMaxU32::new(u32::MAX - 1), None] {
+            let len = value.encode(&mut buffer).unwrap();
+            assert_eq!(<Option<NonMaxU32>>::decode(&buffer[..len]), Ok((value, len)));
+
+            let mut xor = [0; <Option<crate::NonMaxU32> as Varint>::MAX_LEN];
+            assert_eq!(value.map(NonMaxU32::to_xor).encode(&mut xor), Some(len));
+            assert_eq!(buffer[..len], xor[..len]);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_nightly_serde() {
+        use crate::nightly::NonMaxU8;
+
+        assert_eq!(serde_json::from_str::<NonMaxU8>("42").unwrap(), NonMaxU8::new(42).unwrap());
+        assert!(serde_json::from_str::<NonMaxU8>("255").is_err());
+        assert_eq!(serde_json::to_string(&NonMaxU8::new(42).unwrap()).unwrap(), "42");
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_nightly_bytemuck() {
+        use crate::nightly::NonMaxU32;
+
+        let raw: [u32; 3] = [u32::MAX, 7, 0];
+        let values: &[Option<NonMaxU32>] = bytemuck::cast_slice(&raw);
+        assert_eq!(values, [None, NonMaxU32::new(7), NonMaxU32::new(0)]);
+
+        assert_eq!(bytemuck::checked::try_cast::<u32, NonMaxU32>(7).unwrap().get(), 7);
+        assert!(bytemuck::checked::try_cast::<u32, NonMaxU32>(u32::MAX).is_err());
+    }
+
+    #[cfg(feature = "equivalent")]
+    #[test]
+    fn test_nightly_equivalent() {
+        use equivalent::Equivalent;
+        use crate::equivalent::ByValue;
+        use crate::nightly::NonMaxU32;
+
+        let key = NonMaxU32::new(7).unwrap();
+        assert!(7u32.equivalent(&key));
+        assert!(ByValue(crate::NonAnyU32::<5>::new(7).unwrap()).equivalent(&key));
+        assert!(!ByValue(crate::NonMaxU32::new(8).unwrap()).equivalent(&key));
+    }
+}
@@ -0,0 +1,206 @@
+//! Compact variable length encoding of `NonAny*` types and their `Option`s.
+//!
+//! Values are encoded as [LEB128](https://en.wikipedia.org/wiki/LEB128) varints, where every byte stores 7 bits
+//! of the value and the highest bit marks whether more bytes follow. Signed values are first
+//! [zigzag](https://protobuf.dev/programming-guides/encoding/#signed-ints) encoded, so that values close to zero
+//! stay small regardless of their sign.
+//!
+//! Before encoding, values are ranked relative to the niche: values below the niche are shifted up by one, which
+//! leaves rank zero free. An `Option<NonAny*>` uses that rank for `None`, so it is encoded as a single varint
+//! without a separate presence flag, and `None` always costs a single zero byte. A `NonAny*` by itself is encoded
+//! as its rank minus one, so that no encoding is wasted on the niche.
+//!
+//! ```rust
+//! use nonany::{NonMaxU64, NonZeroU64};
+//! use nonany::varint::Varint;
+//!
+//! let mut buffer = [0; <Option<NonMaxU64> as Varint>::MAX_LEN];
+//!
+//! assert_eq!(None::<NonMaxU64>.encode(&mut buffer), Some(1));
+//! assert_eq!(buffer[0], 0);
+//!
+//! // `NonMax*` values are encoded as `value + 1`.
+//! assert_eq!(NonMaxU64::new(300).encode(&mut buffer), Some(2));
+//! assert_eq!(buffer[..2], [0xAD, 0x02]);
+//! assert_eq!(Option::<NonMaxU64>::decode(&buffer), Ok((NonMaxU64::new(300), 2)));
+//!
+//! // `NonZero*` values are encoded as themselves.
+//! assert_eq!(NonZeroU64::new(300).encode(&mut buffer), Some(2));
+//! assert_eq!(buffer[..2], [0xAC, 0x02]);
+//! ```
+
+/// An error that can occur when decoding a varint.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DecodeError {
+    /// The buffer ended before the last byte of the varint.
+    Truncated,
+    /// The varint does not fit in the target type.
+    Overflow,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            DecodeError::Truncated => "varint is truncated",
+            DecodeError::Overflow => "varint does not fit in the target type",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+/// A type that can be encoded as a varint.
+///
+/// This trait is implemented for all `NonAny*` types and their `Option`s, and is sealed.
+pub trait Varint: Sized + sealed::Sealed {
+    /// The maximum number of bytes an encoded value can take.
+    const MAX_LEN: usize;
+
+    /// Encodes the value at the start of the buffer.
+    ///
+    /// Returns the number of bytes written, or `None` if the buffer is too small.
+    /// A buffer of [`MAX_LEN`](Self::MAX_LEN) bytes is always large enough.
+    fn encode(self, buffer: &mut [u8]) -> Option<usize>;
+
+    /// Decodes a value from the start of the buffer.
+    ///
+    /// Returns the value and the number of bytes read.
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), DecodeError>;
+}
+
+macro_rules! nonany_varint {
+    ($nonany:ident, $int:ident, $uint:ident, $signed:ident) => {
+        impl<const NICHE: $int> sealed::Sealed for crate::$nonany<NICHE> {}
+        impl<const NICHE: $int> sealed::Sealed for Option<crate::$nonany<NICHE>> {}
+
+        impl<const NICHE: $int> Varint for Option<crate::$nonany<NICHE>> {
+            const MAX_LEN: usize = (<$uint>::BITS as usize + 6) / 7;
+
+            fn encode(self, buffer: &mut [u8]) -> Option<usize> {
+                let mut rank = match self {
+                    Some(value) => {
+                        let value = nonany_varint!(@to_unsigned, $signed, $int, $uint, value.get());
+                        let niche = nonany_varint!(@to_unsigned, $signed, $int, $uint, NICHE);
+                        if value < niche { value + 1 } else { value }
+                    },
+                    None => 0
+                };
+                nonany_varint!(@encode, rank, buffer)
+            }
+
+            fn decode(buffer: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (rank, len) = nonany_varint!(@decode, $uint, buffer)?;
+                if rank == 0 {
+                    return Ok((None, len));
+                }
+
+                let niche = nonany_varint!(@to_unsigned, $signed, $int, $uint, NICHE);
+                let unsigned = if rank <= niche { rank - 1 } else { rank };
+                let value = nonany_varint!(@from_unsigned, $signed, $int, $uint, unsigned);
+                Ok((crate::$nonany::new(value), len))
+            }
+        }
+
+        impl<const NICHE: $int> Varint for crate::$nonany<NICHE> {
+            const MAX_LEN: usize = (<$uint>::BITS as usize + 6) / 7;
+
+            fn encode(self, buffer: &mut [u8]) -> Option<usize> {
+                let value = nonany_varint!(@to_unsigned, $signed, $int, $uint, self.get());
+                let niche = nonany_varint!(@to_unsigned, $signed, $int, $uint, NICHE);
+                let mut rank = if value < niche { value } else { value - 1 };
+                nonany_varint!(@encode, rank, buffer)
+            }
+
+            fn decode(buffer: &[u8]) -> Result<(Self, usize), DecodeError> {
+                let (rank, len) = nonany_varint!(@decode, $uint, buffer)?;
+                let niche = nonany_varint!(@to_unsigned, $signed, $int, $uint, NICHE);
+                let unsigned = if rank < niche {
+                    rank
+                } else {
+                    match rank.checked_add(1) {
+                        Some(unsigned) => unsigned,
+                        None => return Err(DecodeError::Overflow)
+                    }
+                };
+
+                let value = nonany_varint!(@from_unsigned, $signed, $int, $uint, unsigned);
+                match crate::$nonany::new(value) {
+                    Some(value) => Ok((value, len)),
+                    None => unreachable!()
+                }
+            }
+        }
+    };
+
+    (@encode, $rank:ident, $buffer:ident) => {{
+        let mut len = 0;
+        loop {
+            let byte = ($rank & 0x7f) as u8;
+            $rank >>= 7;
+
+            let slot = $buffer.get_mut(len)?;
+            len += 1;
+            if $rank == 0 {
+                *slot = byte;
+                return Some(len);
+            }
+            *slot = byte | 0x80;
+        }
+    }};
+
+    (@decode, $uint:ident, $buffer:ident) => {{
+        let mut result = Err(DecodeError::Truncated);
+        let mut rank: $uint = 0;
+        let mut shift = 0;
+        for (index, byte) in $buffer.iter().enumerate() {
+            let bits = (byte & 0x7f) as $uint;
+            if shift >= <$uint>::BITS || (bits << shift) >> shift != bits {
+                result = Err(DecodeError::Overflow);
+                break;
+            }
+            rank |= bits << shift;
+
+            if byte & 0x80 == 0 {
+                result = Ok((rank, index + 1));
+                break;
+            }
+            shift += 7;
+        }
+        result
+    }};
+
+    (@to_unsigned, signed, $int:ident, $uint:ident, $value:expr) => {
+        (($value << 1) ^ ($value >> (<$int>::BITS - 1))) as $uint
+    };
+
+    (@to_unsigned, unsigned, $int:ident, $uint:ident, $value:expr) => {
+        $value
+    };
+
+    (@from_unsigned, signed, $int:ident, $uint:ident, $value:expr) => {
+        (($value >> 1) as $int) ^ -(($value & 1) as $int)
+    };
+
+    (@from_unsigned, unsigned, $int:ident, $uint:ident, $value:expr) => {
+        $value
+    };
+}
+
+nonany_varint!(NonAnyI8, i8, u8, signed);
+nonany_varint!(NonAnyI16, i16, u16, signed);
+nonany_varint!(NonAnyI32, i32, u32, signed);
+nonany_varint!(NonAnyI64, i64, u64, signed);
+nonany_varint!(NonAnyI128, i128, u128, signed);
+nonany_varint!(NonAnyIsize, isize, usize, signed);
+
+nonany_varint!(NonAnyU8, u8, u8, unsigned);
+nonany_varint!(NonAnyU16, u16, u16, unsigned);
+nonany_varint!(NonAnyU32, u32, u32, unsigned);
+nonany_varint!(NonAnyU64, u64, u64, unsigned);
+nonany_varint!(NonAnyU128, u128, u128, unsigned);
+nonany_varint!(NonAnyUsize, usize, usize, unsigned);